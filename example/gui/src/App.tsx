@@ -45,20 +45,19 @@ function App() {
                   {(typeof param.value === 'number') && editing == key ?
                     <input
                       autoFocus
-                      type="number"
                       className="w-16 bg-transparent outline-none"
-                      defaultValue={param.value_to_string(param.value as never)}
+                      defaultValue={param.displayText}
                       onKeyUp={e => {
                         if (e.key === 'Enter') {
-                          // @ts-ignore
-                          param.setValue(parseFloat(e.target.value))
+                          param.setValueFromString((e.target as HTMLInputElement).value)
                           setEditing(null);
                         }
                       }}
                     />
-                    : <div>{param.value_to_string(param.value as never)}</div>
+                    : <div>{param.displayText}</div>
                   }
                   {param.unit && <div>{param.unit}</div>}
+                  {param.parseError && <div className="text-red-500">{param.parseError}</div>}
                 </div>
                 <input type="range" className="slider"
                        min={0} max={1} step={0.01}
@@ -67,6 +66,8 @@ function App() {
                          param.setNormalizedValue(parseFloat(e.target.value))
                        }}
                        onDoubleClick={() => param.resetValue()}
+                       onPointerDown={() => param.beginGesture()}
+                       onPointerUp={() => param.endGesture()}
                        onMouseDown={(e) => {
                          if (e.ctrlKey) {
                            e.preventDefault();
@@ -6,9 +6,7 @@ use nih_plug::prelude::*;
 use react_plug::prelude::*;
 
 use include_dir::{include_dir, Dir};
-use serde::{Deserialize, Serialize};
 use std::sync::Arc;
-use ts_rs::TS;
 
 pub struct ExamplePlugin {
     params: Arc<ExampleParams>,
@@ -24,20 +22,34 @@ impl Default for ExamplePlugin {
 
 static EDITOR_DIR: Dir = include_dir!("$CARGO_MANIFEST_DIR/gui/dist");
 
-#[derive(Debug, Serialize, Deserialize, TS)]
-#[ts(export, export_to = "../gui/src/bindings/GuiMessage.ts")]
-enum GuiMessage {
-    Ping,
-    Foo(String),
-    Bar { a: f32, b: f32 },
+define_messages! {
+    enum GuiMessage {
+        Ping,
+        Foo(String),
+        Bar { a: f32, b: f32 },
+    }
+
+    enum PluginMessage {
+        Pong,
+        Oof(String),
+        Baz { a: f32, b: f32 },
+    }
 }
 
-#[derive(Debug, Serialize, Deserialize, TS)]
-#[ts(export, export_to = "../gui/src/bindings/PluginMessage.ts")]
-enum PluginMessage {
-    Pong,
-    Oof(String),
-    Baz { a: f32, b: f32 },
+struct ExampleMessageHandler;
+
+impl GuiMessageHandler for ExampleMessageHandler {
+    fn ping(&self, send: &PluginMessageSender<PluginMessage>) {
+        let _ = send(PluginMessage::Pong);
+    }
+
+    fn foo(&self, value: String, send: &PluginMessageSender<PluginMessage>) {
+        let _ = send(PluginMessage::Oof(value.chars().rev().collect::<String>()));
+    }
+
+    fn bar(&self, a: f32, b: f32, send: &PluginMessageSender<PluginMessage>) {
+        let _ = send(PluginMessage::Baz { a: a / b, b: a * b });
+    }
 }
 
 impl Plugin for ExamplePlugin {
@@ -79,19 +91,11 @@ impl Plugin for ExamplePlugin {
             &EDITOR_DIR,
             (1000, 800),
         )
+        .with_descriptions(&self.params)
         .with_background_color((0, 0, 0, 255))
         .with_developer_mode(true)
-        .with_message_handler(|gui_message, send| match gui_message {
-            GuiMessage::Ping => {
-                let _ = send(PluginMessage::Pong);
-            }
-            GuiMessage::Foo(s) => {
-                let _ = send(PluginMessage::Oof(s.chars().rev().collect::<String>()));
-            }
-            GuiMessage::Bar { a, b } => {
-                let _ = send(PluginMessage::Baz { a: a / b, b: a * b });
-            }
-        })
+        .with_value_text(true)
+        .with_handler(ExampleMessageHandler)
         .into()
     }
 
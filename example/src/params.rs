@@ -2,8 +2,9 @@ use nih_plug::prelude::*;
 use react_plug::prelude::*;
 
 define_params! {
-    ExampleParams {
-        gain: FloatParam {
+    pub ExampleParams {
+        /// The output gain, applied after all other processing.
+        pub gain: FloatParam {
             name: "Gain",
             default_value: util::db_to_gain(0.0),
             range: FloatRange::Skewed {
@@ -16,7 +17,7 @@ define_params! {
             value_to_string: formatters::v2s_f32_gain_to_db(2),
             string_to_value: formatters::s2v_f32_gain_to_db(),
         },
-        reversed: FloatParam {
+        pub reversed: FloatParam {
             name: "Gain",
             default_value: util::db_to_gain(0.0),
             range: FloatRange::Reversed (
@@ -26,16 +27,17 @@ define_params! {
                 }
             ),
         },
-        bool_test: BoolParam {
+        pub bool_test: BoolParam {
             name: "Bool Test",
             default_value: false
         },
-        int_test: IntParam {
+        pub int_test: IntParam {
             name: "Int Test",
+            description: "An integer parameter, for testing",
             default_value: 0,
             range: IntRange::Linear { min: 0, max: 10 }
         },
-        wave_shape: EnumParam {
+        pub wave_shape: EnumParam {
             name: "Wave Shape",
             default_value: Sine,
             variants: Waveform {
@@ -0,0 +1,333 @@
+use crate::ids::{param_id_enum, IdItem};
+use crate::params::{doc_comment, parse_lit_str_array, parse_nested_attr};
+use crate::{bindings_generator, collect_all, nested_param_descriptions, nested_ts_constructor};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::spanned::Spanned;
+use syn::{
+    Attribute, Data, DeriveInput, Error, Expr, ExprLit, Field, Fields, GenericArgument, Lit,
+    LitStr, PathArguments, Type,
+};
+
+/// The TS type and constructor of a single field, or `None` if the field isn't
+/// part of the bindings.
+type FieldBindings = Option<(TokenStream, TokenStream)>;
+
+/// Implements `ParamsBindings` for a struct that derives nih-plug's `Params`, by
+/// reading its `#[id]`, `#[nested]` and `#[persist]` attributes. Parameters are
+/// described by their `ParamBindings` implementations at runtime, so they can be
+/// constructed however the plugin likes, and by their fields' doc comments.
+pub fn expand_derive(input: &DeriveInput) -> syn::Result<TokenStream> {
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(Error::new(
+                    ident.span(),
+                    "ReactPlugParams can only be derived for structs with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(Error::new(
+                ident.span(),
+                "ReactPlugParams can only be derived for structs",
+            ))
+        }
+    };
+
+    let options = parse_options(&input.attrs)?;
+    let bindings = collect_all(fields.iter().map(field_bindings))?;
+    let (type_fields, constructor_fields): (Vec<_>, Vec<_>) =
+        bindings.into_iter().flatten().unzip();
+    let descriptions = collect_all(fields.iter().map(field_descriptions))?;
+    let descriptions = descriptions.into_iter().flatten();
+
+    let bindings_generator = if options.nested || !input.generics.params.is_empty() {
+        quote! {}
+    } else {
+        bindings_generator(ident, options.file_prefix.as_ref(), &options.migrated_ids)
+    };
+
+    let param_id_enum = if input.generics.params.is_empty() {
+        let id_items = collect_all(fields.iter().map(id_item))?;
+        let id_items = id_items.into_iter().flatten().collect::<Vec<_>>();
+        param_id_enum(&input.vis, ident, &id_items)?
+    } else {
+        quote! {}
+    };
+
+    Ok(quote! {
+        impl #impl_generics react_plug::bindings::ParamsBindings for #ident #ty_generics #where_clause {
+            fn ts_type() -> String {
+                react_plug::bindings::ts_object(&[#(#type_fields),*])
+            }
+
+            #[allow(unused_variables)]
+            fn ts_constructor(&self, id: &dyn Fn(&str) -> String) -> String {
+                react_plug::bindings::ts_object(&[#(#constructor_fields),*])
+            }
+
+            #[allow(unused_variables, unused_mut)]
+            fn param_descriptions(&self, id: &dyn Fn(&str) -> String) -> Vec<(String, String)> {
+                let mut descriptions = vec![];
+                #(#descriptions)*
+                descriptions
+            }
+        }
+
+        #bindings_generator
+
+        #param_id_enum
+    })
+}
+
+/// The field as part of the struct's ID enum, or `None` if it isn't a parameter or
+/// a nested struct.
+fn id_item(field: &Field) -> syn::Result<Option<IdItem<'_>>> {
+    if parse_options(&field.attrs)?.skip {
+        return Ok(None);
+    }
+
+    let ident = field.ident.as_ref().unwrap();
+    for attr in &field.attrs {
+        if attr.path().is_ident("id") {
+            let id = attr_value(attr)?;
+            return Ok(Some(IdItem::Param(ident, quote! { #id })));
+        }
+
+        if attr.path().is_ident("nested") {
+            let (id_prefix, array) = parse_nested_attr(attr)?;
+            let (ty, len) = match &field.ty {
+                Type::Array(ty) if array => (&*ty.elem, Some(&ty.len)),
+                ty if array => (array_elem(ty)?, None),
+                ty => (ty, None),
+            };
+
+            return Ok(Some(IdItem::Nested {
+                ident,
+                ty: generic_arg(ty, &["Arc"]).unwrap_or(ty),
+                id_prefix: if array { None } else { id_prefix },
+                array,
+                len,
+            }));
+        }
+    }
+
+    Ok(None)
+}
+
+fn field_bindings(field: &Field) -> syn::Result<FieldBindings> {
+    if parse_options(&field.attrs)?.skip {
+        return Ok(None);
+    }
+
+    let ident = field.ident.as_ref().unwrap();
+    let name = ident.to_string();
+    let ty = &field.ty;
+
+    for attr in &field.attrs {
+        if attr.path().is_ident("id") {
+            let id = attr_value(attr)?;
+            let (type_field, description) = match doc_comment(&field.attrs) {
+                Some(description) => (
+                    quote! {
+                        format!(
+                            "{}{}: {}",
+                            react_plug::bindings::ts_doc_comment(#description),
+                            #name,
+                            <#ty as react_plug::bindings::ParamBindings>::ts_type()
+                        )
+                    },
+                    quote! { Some(#description) },
+                ),
+                None => (
+                    quote! {
+                        format!("{}: {}", #name, <#ty as react_plug::bindings::ParamBindings>::ts_type())
+                    },
+                    quote! { None },
+                ),
+            };
+
+            return Ok(Some((
+                type_field,
+                quote! {
+                    format!(
+                        "{}: {}",
+                        #name,
+                        react_plug::bindings::ParamBindings::ts_constructor(&self.#ident, &id(#id), #description)
+                    )
+                },
+            )));
+        }
+
+        if attr.path().is_ident("nested") {
+            let (id_prefix, array) = parse_nested_attr(attr)?;
+            let type_field = if array {
+                let elem = array_elem(ty)?;
+                quote! {
+                    format!("{}: {}[]", #name, <#elem as react_plug::bindings::ParamsBindings>::ts_type())
+                }
+            } else {
+                quote! {
+                    format!("{}: {}", #name, <#ty as react_plug::bindings::ParamsBindings>::ts_type())
+                }
+            };
+
+            return Ok(Some((
+                type_field,
+                nested_ts_constructor(ident, array, id_prefix.as_ref()),
+            )));
+        }
+
+        if attr.path().is_ident("persist") {
+            let key = attr_value(attr)?;
+            let value_ty = persisted_type(ty)?;
+            return Ok(Some((
+                quote! {
+                    format!("{}: ReactPlug.PersistentField<{}>", #name, <#value_ty as react_plug::bindings::TS>::inline())
+                },
+                quote! {
+                    format!(
+                        "{}: new ReactPlug.PersistentField({{ key: {:?}, defaultValue: {} }})",
+                        #name,
+                        id(#key),
+                        react_plug::bindings::ts_persisted_value(&self.#ident)
+                    )
+                },
+            )));
+        }
+    }
+
+    Ok(None)
+}
+
+/// Adds the description of a parameter, or those of a nested struct, to
+/// `descriptions` in `ParamsBindings::param_descriptions`.
+fn field_descriptions(field: &Field) -> syn::Result<Option<TokenStream>> {
+    if parse_options(&field.attrs)?.skip {
+        return Ok(None);
+    }
+
+    let ident = field.ident.as_ref().unwrap();
+    for attr in &field.attrs {
+        if attr.path().is_ident("id") {
+            let id = attr_value(attr)?;
+            return Ok(doc_comment(&field.attrs).map(|description| {
+                quote! {
+                    descriptions.push((id(#id), String::from(#description)));
+                }
+            }));
+        }
+
+        if attr.path().is_ident("nested") {
+            let (id_prefix, array) = parse_nested_attr(attr)?;
+            return Ok(Some(nested_param_descriptions(
+                ident,
+                array,
+                id_prefix.as_ref(),
+            )));
+        }
+    }
+
+    Ok(None)
+}
+
+/// The options of `#[react_plug(...)]` attributes. Structs can be marked as
+/// `nested` and given a `file_prefix` and `migrated_ids`, fields can be skipped
+/// using `skip`.
+#[derive(Default)]
+struct Options {
+    skip: bool,
+    nested: bool,
+    file_prefix: Option<LitStr>,
+    migrated_ids: Vec<LitStr>,
+}
+
+fn parse_options(attrs: &[Attribute]) -> syn::Result<Options> {
+    let mut options = Options::default();
+    for attr in attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("react_plug"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("skip") {
+                options.skip = true;
+            } else if meta.path.is_ident("nested") {
+                options.nested = true;
+            } else if meta.path.is_ident("file_prefix") {
+                options.file_prefix = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("migrated_ids") {
+                options.migrated_ids = parse_lit_str_array(meta.value()?)?;
+            } else {
+                return Err(
+                    meta.error("Expected `skip`, `nested`, `file_prefix` or `migrated_ids`")
+                );
+            }
+            Ok(())
+        })?;
+    }
+
+    Ok(options)
+}
+
+/// The string value of an attribute like `#[id = "gain"]`.
+fn attr_value(attr: &Attribute) -> syn::Result<LitStr> {
+    match &attr.meta.require_name_value()?.value {
+        Expr::Lit(ExprLit {
+            lit: Lit::Str(value),
+            ..
+        }) => Ok(value.clone()),
+        value => Err(Error::new(value.span(), "Expected a string literal")),
+    }
+}
+
+/// The element type of a nested parameter array, e.g. `BandParams` for
+/// `[BandParams; 4]` or `Vec<BandParams>`.
+fn array_elem(ty: &Type) -> syn::Result<&Type> {
+    match ty {
+        Type::Array(array) => Ok(&array.elem),
+        ty => generic_arg(ty, &["Vec"]).ok_or_else(|| {
+            Error::new(
+                ty.span(),
+                "Nested parameter arrays need to be arrays or `Vec`s, e.g. [BandParams; 4]",
+            )
+        }),
+    }
+}
+
+/// The type of a persisted field's value, e.g. `MidiMappings` for
+/// `Arc<RwLock<MidiMappings>>`.
+fn persisted_type(ty: &Type) -> syn::Result<&Type> {
+    let lock = generic_arg(ty, &["Arc"]).unwrap_or(ty);
+
+    generic_arg(lock, &["RwLock", "Mutex", "AtomicRefCell"]).ok_or_else(|| {
+        Error::new(
+            ty.span(),
+            "Expected a persisted field like `Arc<RwLock<T>>`, use #[react_plug(skip)] to leave it out of the bindings",
+        )
+    })
+}
+
+/// The single generic argument of a type, if it's one of `wrappers`.
+pub fn generic_arg<'a>(ty: &'a Type, wrappers: &[&str]) -> Option<&'a Type> {
+    let segment = match ty {
+        Type::Path(path) => path.path.segments.last()?,
+        _ => return None,
+    };
+
+    if !wrappers.iter().any(|wrapper| segment.ident == wrapper) {
+        return None;
+    }
+
+    match &segment.arguments {
+        PathArguments::AngleBracketed(args) if args.args.len() == 1 => match &args.args[0] {
+            GenericArgument::Type(ty) => Some(ty),
+            _ => None,
+        },
+        _ => None,
+    }
+}
@@ -0,0 +1,192 @@
+use heck::ToUpperCamelCase;
+use proc_macro2::{Ident, TokenStream};
+use quote::{format_ident, quote};
+use syn::spanned::Spanned;
+use syn::{Error, Expr, LitStr, Type, Visibility};
+
+/// A field of a parameters struct that's part of its ID enum.
+pub enum IdItem<'a> {
+    /// A parameter and its ID.
+    Param(&'a Ident, TokenStream),
+    /// A nested parameters struct. `len` is the length of a nested array, if it's
+    /// known.
+    Nested {
+        ident: &'a Ident,
+        ty: &'a Type,
+        id_prefix: Option<LitStr>,
+        array: bool,
+        len: Option<&'a Expr>,
+    },
+}
+
+/// Generates the ID enum of a parameters struct, e.g. `ExampleParamsId` for
+/// `ExampleParams`. Parameters become unit variants, nested structs wrap the ID enum
+/// of their own struct, and arrays of nested structs also hold the index.
+pub fn param_id_enum(
+    vis: &Visibility,
+    ident: &Ident,
+    items: &[IdItem],
+) -> syn::Result<TokenStream> {
+    let id_ident = format_ident!("{}Id", ident);
+    let doc = format!(
+        "The IDs of the parameters of [{}], including the nested ones.",
+        ident
+    );
+
+    let mut variants = vec![];
+    let mut as_str_arms = vec![];
+    let mut from_id_checks = vec![];
+
+    for item in items {
+        match item {
+            IdItem::Param(ident, id) => {
+                let variant = variant_ident(ident);
+                variants.push(quote! { #variant });
+                as_str_arms.push(quote! {
+                    Self::#variant => std::borrow::Cow::Borrowed(#id)
+                });
+                from_id_checks.push(quote! {
+                    if id == #id {
+                        return Some(Self::#variant);
+                    }
+                });
+            }
+            IdItem::Nested {
+                ident,
+                ty,
+                id_prefix,
+                array,
+                len,
+            } => {
+                let variant = variant_ident(ident);
+                let nested_id = nested_id_type(ty)?;
+
+                if *array {
+                    let in_bounds = len
+                        .map(|len| quote! { index < #len })
+                        .unwrap_or_else(|| quote! { true });
+                    variants.push(quote! { #variant(usize, #nested_id) });
+                    as_str_arms.push(quote! {
+                        Self::#variant(index, id) => std::borrow::Cow::Owned(
+                            react_plug::bindings::array_id(&react_plug::ParamId::as_str(id), *index)
+                        )
+                    });
+                    from_id_checks.push(quote! {
+                        if let Some((id, index)) = react_plug::bindings::split_array_id(id) {
+                            if let Some(id) = <#nested_id as react_plug::ParamId>::from_id(id) {
+                                if #in_bounds {
+                                    return Some(Self::#variant(index, id));
+                                }
+                            }
+                        }
+                    });
+                } else if let Some(id_prefix) = id_prefix {
+                    variants.push(quote! { #variant(#nested_id) });
+                    as_str_arms.push(quote! {
+                        Self::#variant(id) => std::borrow::Cow::Owned(
+                            react_plug::bindings::prefixed_id(#id_prefix, &react_plug::ParamId::as_str(id))
+                        )
+                    });
+                    from_id_checks.push(quote! {
+                        if let Some(id) = react_plug::bindings::strip_id_prefix(#id_prefix, id)
+                            .and_then(<#nested_id as react_plug::ParamId>::from_id)
+                        {
+                            return Some(Self::#variant(id));
+                        }
+                    });
+                } else {
+                    variants.push(quote! { #variant(#nested_id) });
+                    as_str_arms.push(quote! {
+                        Self::#variant(id) => react_plug::ParamId::as_str(id)
+                    });
+                    from_id_checks.push(quote! {
+                        if let Some(id) = <#nested_id as react_plug::ParamId>::from_id(id) {
+                            return Some(Self::#variant(id));
+                        }
+                    });
+                }
+            }
+        }
+    }
+
+    // Matching on a reference to an empty enum needs to dereference it
+    let as_str = if items.is_empty() {
+        quote! { match *self {} }
+    } else {
+        quote! { match self { #(#as_str_arms),* } }
+    };
+
+    Ok(quote! {
+        #[doc = #doc]
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        #vis enum #id_ident {
+            #(#variants),*
+        }
+
+        impl react_plug::ParamId for #id_ident {
+            fn as_str(&self) -> std::borrow::Cow<'_, str> {
+                #as_str
+            }
+
+            #[allow(unused_variables)]
+            fn from_id(id: &str) -> Option<Self> {
+                #(#from_id_checks)*
+                None
+            }
+        }
+
+        impl #id_ident {
+            /// The ID as it's used by nih-plug and the GUI.
+            pub fn as_str(&self) -> std::borrow::Cow<'_, str> {
+                react_plug::ParamId::as_str(self)
+            }
+        }
+
+        impl std::str::FromStr for #id_ident {
+            type Err = react_plug::UnknownParamId;
+
+            fn from_str(id: &str) -> Result<Self, Self::Err> {
+                <Self as react_plug::ParamId>::from_id(id)
+                    .ok_or_else(|| react_plug::UnknownParamId(id.to_string()))
+            }
+        }
+
+        impl std::fmt::Display for #id_ident {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.write_str(&self.as_str())
+            }
+        }
+    })
+}
+
+fn variant_ident(ident: &Ident) -> Ident {
+    format_ident!(
+        "{}",
+        ident.to_string().to_upper_camel_case(),
+        span = ident.span()
+    )
+}
+
+/// The ID enum of a nested parameters struct, e.g. `bands::BandParamsId` for
+/// `bands::BandParams`.
+fn nested_id_type(ty: &Type) -> syn::Result<Type> {
+    match ty {
+        Type::Path(path) if path.qself.is_none() => {
+            let mut path = path.clone();
+            let segment = path.path.segments.last_mut().unwrap();
+            if !segment.arguments.is_empty() {
+                return Err(Error::new(
+                    ty.span(),
+                    "Nested parameter structs can't be generic",
+                ));
+            }
+
+            segment.ident = format_ident!("{}Id", segment.ident);
+            Ok(Type::Path(path))
+        }
+        _ => Err(Error::new(
+            ty.span(),
+            "Expected the name of a parameters struct",
+        )),
+    }
+}
@@ -3,173 +3,331 @@ extern crate proc_macro;
 use crate::params::RPParamType;
 use heck::ToLowerCamelCase;
 use params::*;
+use proc_macro2::Ident;
 use quote::{format_ident, quote, ToTokens};
+use std::collections::HashSet;
 use std::ops::Deref;
-use syn::{Expr, Member};
+use syn::{Error, Expr, LitStr, Path, Type};
 
+mod derive;
+mod ids;
+mod messages;
 mod params;
+mod validation;
 
-fn try_find_field(param: &RPParam, ident: &str) -> Result<Expr, String> {
+fn find_field(param: &RPParam, ident: &str) -> syn::Result<Expr> {
     param
         .fields
         .iter()
         .find(|field| field.ident == ident)
         .map(|field| field.expr.clone())
-        .ok_or(format!(
-            r#"Couldn't find field "{}" in parameter "{}""#,
-            ident, param.ident
-        ))
+        .ok_or_else(|| {
+            Error::new(
+                param.ident.span(),
+                format!("Missing field `{}` in parameter `{}`", ident, param.ident),
+            )
+        })
 }
 
-fn find_field(param: &RPParam, ident: &str) -> Expr {
-    try_find_field(param, ident).unwrap_or_else(|err| panic!("{}", err))
+/// Collects all values, or all errors combined into a single one, so that they are
+/// reported at once.
+fn collect_all<T>(results: impl IntoIterator<Item = syn::Result<T>>) -> syn::Result<Vec<T>> {
+    let mut values = vec![];
+    let mut error: Option<Error> = None;
+
+    for result in results {
+        match result {
+            Ok(value) => values.push(value),
+            Err(err) => match &mut error {
+                Some(error) => error.combine(err),
+                None => error = Some(err),
+            },
+        }
+    }
+
+    error.map_or(Ok(values), Err)
 }
 
-fn find_fields<'a>(
-    param: &'a RPParam,
-    idents: impl IntoIterator<Item = &'static str> + 'a,
-) -> impl Iterator<Item = Expr> + 'a {
-    idents
-        .into_iter()
-        .map(move |ident| find_field(param, ident))
+/// Checks a parameter declaration for missing, unknown and invalid fields.
+fn validate_param(param: &RPParam) -> syn::Result<()> {
+    let ty = &param.ty;
+    let mut results = vec![];
+    let mut seen = HashSet::new();
+
+    for field in &param.fields {
+        let ident = field.ident.to_string();
+
+        if !seen.insert(ident.clone()) {
+            results.push(Err(Error::new(
+                field.ident.span(),
+                format!("Duplicate field `{}`", ident),
+            )));
+        } else if ident != "id"
+            && ident != "description"
+            && !ty.constructor_fields().contains(&ident.as_str())
+            && !ty.modifier_fields().contains(&ident.as_str())
+        {
+            results.push(Err(Error::new(
+                field.ident.span(),
+                format!("Unknown field `{}` for {}", ident, ty.to_token_stream()),
+            )));
+        }
+    }
+
+    for ident in ty.constructor_fields() {
+        results.push(find_field(param, ident).map(drop));
+    }
+
+    if let Ok(variants) = find_field(param, "variants") {
+        results.push(enum_type(&variants).map(drop));
+    }
+
+    if let Ok(range) = find_field(param, "range") {
+        results.push(range_to_ts(&range, ty).map(drop));
+    }
+
+    results.push(param.description().map(drop));
+
+    results.push(validation::validate_values(param));
+
+    collect_all(results).map(drop)
+}
+
+/// The enum type of an enum parameter. This is either declared along with its
+/// variants, or the path of an existing type that implements nih-plug's `Enum`,
+/// e.g. `dsp::Waveform`.
+fn enum_type(expr: &Expr) -> syn::Result<Path> {
+    match expr {
+        Expr::Path(path) if path.qself.is_none() => Ok(path.path.clone()),
+        expr => enum_variants(expr).map(|variants| variants.path),
+    }
+}
+
+/// The identifiers of an enum's variants as an `Option<&[&str]>` expression. They're
+/// only known if the variants are declared along with the enum's name, and used as
+/// the variants' keys in TS if they don't have an `#[id]`.
+fn enum_variant_idents(expr: &Expr) -> syn::Result<proc_macro2::TokenStream> {
+    if let Expr::Path(_) = expr {
+        return Ok(quote! { None });
+    }
+
+    let idents = enum_variants(expr)?
+        .variants
+        .iter()
+        .map(|variant| variant.ident.to_string())
+        .collect::<Vec<_>>();
+
+    Ok(quote! { Some(&[#(#idents),*]) })
+}
+
+/// The variants of an enum parameter, declared along with the enum's name, e.g.
+/// `Waveform { Sine: { name: "Sine Wave", id: "sine" }, Dirac }`. Either all
+/// variants have an ID, or none of them do.
+fn enum_variants(expr: &Expr) -> syn::Result<RPEnumVariants> {
+    let variants: RPEnumVariants = if let Expr::Verbatim(tokens) = expr {
+        syn::parse2(tokens.clone())?
+    } else {
+        return Err(Error::new_spanned(
+            expr,
+            "Expected the variants of an enum, e.g. `Waveform { Sine: \"Sine Wave\", Dirac }`, or the path of an existing enum",
+        ));
+    };
+
+    let has_ids = variants.variants.iter().any(|variant| variant.id.is_some());
+    let mut ids = HashSet::new();
+    collect_all(variants.variants.iter().map(|variant| match &variant.id {
+        None if has_ids => Err(Error::new(
+            variant.ident.span(),
+            format!(
+                "Missing id for variant `{}`, either all variants or none of them need an id",
+                variant.ident
+            ),
+        )),
+        Some(id) if !ids.insert(id.value()) => Err(Error::new(
+            id.span(),
+            format!("Duplicate variant id `{}`", id.value()),
+        )),
+        _ => Ok(()),
+    }))?;
+
+    Ok(variants)
 }
 
 // TODO: Skipping fields
-// TODO: Arbitrary data types (that are ignored by TS)
 #[proc_macro]
 pub fn define_params(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
-    let params = syn::parse::<RPParams>(input).unwrap();
+    let params = syn::parse_macro_input!(input as RPParams);
+
+    expand_params(&params)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// Generates the TS bindings for an existing nih-plug `Params` struct, keeping its
+/// declaration and `Default` implementation as written. See `USAGE.MD`.
+#[proc_macro_derive(ReactPlugParams, attributes(react_plug))]
+pub fn derive_react_plug_params(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = syn::parse_macro_input!(input as syn::DeriveInput);
+
+    derive::expand_derive(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// Declares the `GuiMessage` and `PluginMessage` enums, a `GuiMessageHandler` trait
+/// with a method for each GUI message, and their TS bindings. See `USAGE.MD`.
+#[proc_macro]
+pub fn define_messages(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let messages = syn::parse_macro_input!(input as messages::RPMessages);
+
+    messages::expand_messages(&messages).into()
+}
+
+fn expand_params(params: &RPParams) -> syn::Result<proc_macro2::TokenStream> {
+    collect_all(
+        params
+            .params()
+            .map(validate_param)
+            .chain(std::iter::once(validation::validate_ids(params))),
+    )?;
+
     let ident = &params.ident;
 
-    let fields = params.params.iter().map(|param| {
+    let fields = collect_all(params.items.iter().map(|item| -> syn::Result<_> {
+        let param = match item {
+            RPItem::Param(param) => param,
+            RPItem::Nested(nested) => {
+                let attrs = &nested.attrs;
+                let attr = &nested.attr;
+                let vis = &nested.vis;
+                let name = &nested.ident;
+                let ty = &nested.ty;
+                return Ok(quote! {
+                    #(#attrs)*
+                    #attr
+                    #vis #name: #ty
+                });
+            }
+            RPItem::Persist(persist) => {
+                let attrs = &persist.attrs;
+                let vis = &persist.vis;
+                let name = &persist.ident;
+                let key = persist.key();
+                let ty = &persist.ty;
+                return Ok(quote! {
+                    #(#attrs)*
+                    #[persist = #key]
+                    #vis #name: std::sync::Arc<std::sync::RwLock<#ty>>
+                });
+            }
+        };
+        // Doc comments are replaced by the description, which may come from a field
+        let attrs = param
+            .attrs
+            .iter()
+            .filter(|attr| !attr.path().is_ident("doc"));
+        let vis = &param.vis;
         let name = &param.ident;
         let ty = &param.ty;
 
         let ty = if ty == &RPParamType::EnumParam {
-            let associated = if let Expr::Struct(s) = find_field(param, "variants") {
-                s.path
-            } else {
-                panic!("Invalid syntax for \"variants\" field!");
-            };
+            let variants = find_field(param, "variants")?;
+            let associated = enum_type(&variants)?;
             quote! {#ty<#associated>}
         } else {
             quote! {#ty}
         };
 
-        try_find_field(param, "id")
-            .map(|id| {
-                quote! {
-                    #[id = #id]
-                    pub #name: #ty
-                }
-            })
-            .unwrap_or_else(|_| {
-                let id = name.to_string();
-                quote! {
-                    #[id = #id]
-                    pub #name: #ty
-                }
-            })
-    });
+        let id = param_id(param);
+        let description = param.description()?.into_iter();
 
-    let param_enums = params
-        .params
-        .iter()
-        .filter(|param| param.ty == RPParamType::EnumParam)
-        .map(|param| {
-            let (ident, variants) = if let Expr::Struct(s) = find_field(param, "variants") {
-                (
-                    s.path,
-                    s.fields.into_iter().map(|field| {
-                        let id = if let Member::Named(ident) = &field.member {
-                            ident
-                        } else {
-                            panic!(
-                                "Invalid syntax for \"variants\" field! Expected a named field."
-                            );
-                        };
-
-                        if let Expr::Lit(name) = &field.expr {
-                            quote! {
-                                #[name = #name]
-                                #id
-                            }
-                        } else {
-                            id.to_token_stream()
-                        }
-                    }),
-                )
-            } else {
-                panic!("Invalid syntax for \"variants\" field!");
-            };
-
-            quote! {
-                #[derive(nih_plug::params::enums::Enum, PartialEq)]
-                pub enum #ident {
-                    #(#variants),*
+        Ok(quote! {
+            #(#[doc = #description])*
+            #(#attrs)*
+            #[id = #id]
+            #vis #name: #ty
+        })
+    }))?;
+
+    let param_enums = collect_all(
+        params
+            .params()
+            .filter(|param| param.ty == RPParamType::EnumParam)
+            .map(|param| -> syn::Result<_> {
+                let variants = find_field(param, "variants")?;
+                if let Expr::Path(_) = variants {
+                    return Ok(None);
                 }
-            }
-        });
 
-    let defaults = params.params.iter().map(|param| {
+                let vis = &param.vis;
+                let RPEnumVariants {
+                    path: ident,
+                    variants,
+                    ..
+                } = enum_variants(&variants)?;
+                let variants = variants.iter().map(|variant| {
+                    let ident = &variant.ident;
+                    let id = variant.id.iter();
+                    let name = variant.name.iter();
+                    quote! {
+                        #(#[id = #id])*
+                        #(#[name = #name])*
+                        #ident
+                    }
+                });
+
+                Ok(Some(quote! {
+                    #[derive(nih_plug::params::enums::Enum, PartialEq)]
+                    #vis enum #ident {
+                        #(#variants),*
+                    }
+                }))
+            }),
+    )?;
+
+    let defaults = collect_all(params.items.iter().map(|item| -> syn::Result<_> {
+        let param = match item {
+            RPItem::Param(param) => param,
+            RPItem::Nested(nested) => {
+                let ident = &nested.ident;
+                return Ok(if nested.array {
+                    quote! { #ident: std::array::from_fn(|_| Default::default()) }
+                } else {
+                    quote! { #ident: Default::default() }
+                });
+            }
+            RPItem::Persist(persist) => {
+                let ident = &persist.ident;
+                let default_value = persist_default_value(persist);
+                return Ok(quote! {
+                    #ident: std::sync::Arc::new(std::sync::RwLock::new(#default_value))
+                });
+            }
+        };
         let ident = &param.ident;
         let ty = &param.ty;
 
         let args = if ty == &RPParamType::EnumParam {
-            let name = find_field(param, "name");
-            let default_value = find_field(param, "default_value");
-            let ident = if let Expr::Struct(s) = find_field(param, "variants") {
-                s.path
-            } else {
-                panic!("Invalid syntax for \"variants\" field!");
-            };
+            let name = find_field(param, "name")?;
+            let default_value = find_field(param, "default_value")?;
+            let variants = find_field(param, "variants")?;
+            let ident = enum_type(&variants)?;
 
             quote! { #name, #ident::#default_value }
         } else {
-            let field_values = find_fields(
-                param,
-                match ty {
-                    RPParamType::FloatParam | RPParamType::IntParam => {
-                        vec!["name", "default_value", "range"]
-                    }
-                    _ => vec!["name", "default_value"],
-                },
-            );
+            let field_values = collect_all(
+                ty.constructor_fields()
+                    .iter()
+                    .map(|ident| find_field(param, ident)),
+            )?;
             quote! { #(#field_values),* }
         };
 
-        let modifier_idents = match ty {
-            RPParamType::FloatParam => vec![
-                "callback",
-                "poly_modulation_id",
-                "smoother",
-                "step_size",
-                "string_to_value",
-                "unit",
-                "value_to_string",
-            ],
-            RPParamType::IntParam => vec![
-                "callback",
-                "poly_modulation_id",
-                "smoother",
-                "string_to_value",
-                "unit",
-                "value_to_string",
-            ],
-            RPParamType::BoolParam => vec![
-                "callback",
-                "poly_modulation_id",
-                "string_to_value",
-                "value_to_string",
-            ],
-            RPParamType::EnumParam => vec!["callback", "poly_modulation_id"],
-        }
-        .into_iter()
-        .filter_map(|ident| {
+        let modifier_idents = ty.modifier_fields().iter().filter_map(|ident| {
             param
                 .fields
                 .iter()
-                .find(|field| field.ident.to_string() == ident)
+                .find(|field| field.ident == ident)
                 .map(|field| {
                     let ident = format_ident!("with_{}", ident);
                     let expr = field.expr.clone();
@@ -177,228 +335,431 @@ pub fn define_params(input: proc_macro::TokenStream) -> proc_macro::TokenStream
                 })
         });
 
-        quote! {
+        Ok(quote! {
             #ident: #ty::new(#args)#(#modifier_idents)*
+        })
+    }))?;
+
+    let id_items = params
+        .items
+        .iter()
+        .filter_map(|item| match item {
+            RPItem::Param(param) => Some(ids::IdItem::Param(&param.ident, param_id(param))),
+            RPItem::Nested(nested) => Some(match &nested.ty {
+                Type::Array(array) if nested.array => ids::IdItem::Nested {
+                    ident: &nested.ident,
+                    ty: &array.elem,
+                    id_prefix: None,
+                    array: true,
+                    len: Some(&array.len),
+                },
+                ty => ids::IdItem::Nested {
+                    ident: &nested.ident,
+                    ty: derive::generic_arg(ty, &["Arc"]).unwrap_or(ty),
+                    id_prefix: nested.id_prefix.clone(),
+                    array: false,
+                    len: None,
+                },
+            }),
+            RPItem::Persist(_) => None,
+        })
+        .collect::<Vec<_>>();
+    let vis = &params.vis;
+    let param_id_enum = ids::param_id_enum(vis, ident, &id_items)?;
+
+    let debug_assertions = params.params().map(validation::debug_assertions);
+
+    let bindings = generate_ts_bindings(params)?;
+
+    let attrs = &params.attrs;
+
+    Ok(quote! {
+        #(#attrs)*
+        #[derive(nih_plug::params::Params)]
+        #vis struct #ident {
+            #(#fields),*
         }
-    });
 
-    let bindings = generate_ts_bindings(&params);
+        #(#param_enums)*
 
-    {
-        quote! {
-            #[derive(nih_plug::params::Params)]
-            pub struct #ident {
-                #(#fields),*
-            }
+        #param_id_enum
 
-            #(#param_enums)*
+        impl Default for #ident {
+            fn default() -> Self {
+                #(#debug_assertions)*
 
-            impl Default for #ident {
-                fn default() -> Self {
-                    Self {
-                        #(#defaults),*
-                    }
+                Self {
+                    #(#defaults),*
                 }
             }
-
-            #bindings
         }
-    }
-    .into()
-}
 
-fn generate_ts_bindings(params: &RPParams) -> proc_macro2::TokenStream {
-    let mut args = vec![];
+        #bindings
+    })
+}
 
-    let param_type_def = params
-        .params
-        .iter()
-        .map(|param| {
-            format!(
-                "{}: ReactPlug.parameters.{}",
-                param.ident.to_token_stream().to_string(),
-                param.ty.to_token_stream().to_string()
-            )
-        })
-        .collect::<Vec<_>>()
-        .join(", \n    ");
+/// The parameter's `id` field, or its identifier if it doesn't have one.
+fn param_id(param: &RPParam) -> proc_macro2::TokenStream {
+    find_field(param, "id")
+        .map(|id| id.to_token_stream())
+        .unwrap_or_else(|_| param.ident.to_string().to_token_stream())
+}
 
-    let param_defaults = params
-        .params
-        .iter()
-        .map(|param| {
-            let param_ident = &param.ident.to_string();
-            let param_ty = &param.ty.to_token_stream().to_string();
-            let mut options = vec![];
+fn generate_ts_bindings(params: &RPParams) -> syn::Result<proc_macro2::TokenStream> {
+    let ident = &params.ident;
 
-            param
-                .fields
-                .iter()
-                .for_each(|field| match field.ident.to_string().as_str() {
-                    "smoother" => {}
-                    "range" => {
-                        let (range_options, range_args) = range_to_ts(&field.expr, &param.ty);
-                        options.push(format!("range: {}", range_options));
-                        args.extend(range_args);
-                    }
-                    "value_to_string" => {
-                        if let Expr::Call(call) = &field.expr {
-                            if call
-                                .func
-                                .to_token_stream()
-                                .to_string()
-                                .split("::")
-                                .next()
-                                .unwrap()
-                                .trim()
-                                == "formatters"
-                            {
-                                call.args
-                                    .iter()
-                                    .for_each(|arg| args.push(arg.to_token_stream()));
-                                options.push(format!(
-                                    "value_to_string: ReactPlug.formatters.{}({})",
-                                    call.func
-                                        .to_token_stream()
-                                        .to_string()
-                                        .split("::")
-                                        .last()
-                                        .unwrap()
-                                        .trim(),
-                                    "{}".repeat(call.args.len())
-                                ));
-                            }
-                        }
-                    }
-                    "string_to_value" => {
-                        // TODO
-                    }
-                    "variants" => {
-                        let variants = if let Expr::Struct(variants) = &field.expr {
-                            variants
-                                .fields
-                                .iter()
-                                .map(|field| {
-                                    let id = if let Member::Named(ident) = &field.member {
-                                        ident.to_token_stream().to_string()
-                                    } else {
-                                        panic!("Invalid syntax for \"variants\" field");
-                                    };
-
-                                    let name = if let Expr::Lit(name) = &field.expr {
-                                        if let syn::Lit::Str(s) = &name.lit {
-                                            s.value()
-                                        } else {
-                                            panic!("Invalid syntax for \"variants\" field");
-                                        }
-                                    } else {
-                                        id.clone()
-                                    };
-
-                                    format!(r#""{id}": "{name}""#)
-                                })
-                                .collect::<Vec<String>>()
-                                .join(", ")
-                        } else {
-                            panic!("Invalid syntax for \"variants\" field");
-                        };
-
-                        options.push(format!("variants: {{{{ {} }}}}", variants))
-                    }
-                    "default_value" => {
-                        if param.ty == RPParamType::EnumParam {
-                            options.push(format!(
-                                "defaultValue: \"{}\"",
-                                field.expr.to_token_stream().to_string()
-                            ));
-                        } else {
-                            options.push("defaultValue: {}".to_string());
-                            args.push(field.expr.to_token_stream());
+    let type_fields = collect_all(params.items.iter().map(|item| -> syn::Result<_> {
+        Ok(match item {
+            RPItem::Param(param) => {
+                let type_field = format!(
+                    "{}: ReactPlug.parameters.{}",
+                    param.ident.to_token_stream().to_string(),
+                    param.ty.to_token_stream().to_string()
+                );
+                match param.description()? {
+                    Some(description) => quote! {
+                        format!("{}{}", react_plug::bindings::ts_doc_comment(#description), #type_field)
+                    },
+                    None => quote! { String::from(#type_field) },
+                }
+            }
+            RPItem::Nested(nested) => {
+                let name = nested.ident.to_string();
+                match &nested.ty {
+                    Type::Array(array) if nested.array => {
+                        let elem = &array.elem;
+                        quote! {
+                            format!("{}: {}[]", #name, <#elem as react_plug::bindings::ParamsBindings>::ts_type())
                         }
                     }
-                    ident => {
-                        options.push(format!(
-                            "{}: {{:?}}",
-                            ident.to_string().to_lower_camel_case()
-                        ));
-                        args.push(field.expr.to_token_stream());
-                    }
-                });
-
-            if let Err(_) = try_find_field(param, "id") {
-                options.push(format!("id: \"{}\"", param_ident));
+                    ty => quote! {
+                        format!("{}: {}", #name, <#ty as react_plug::bindings::ParamsBindings>::ts_type())
+                    },
+                }
+            }
+            RPItem::Persist(persist) => {
+                let name = persist.ident.to_string();
+                let ty = &persist.ty;
+                quote! {
+                    format!("{}: ReactPlug.PersistentField<{}>", #name, <#ty as react_plug::bindings::TS>::inline())
+                }
             }
+        })
+    }))?;
 
-            format!(
-                "{}: new ReactPlug.parameters.{}({{{{ {} }}}})",
-                param_ident,
-                param_ty,
-                options.join(", ")
-            )
+    let constructor_fields = collect_all(params.items.iter().map(ts_constructor_field))?;
+
+    let descriptions = collect_all(params.items.iter().map(|item| -> syn::Result<_> {
+        Ok(match item {
+            RPItem::Param(param) => param.description()?.map(|description| {
+                let id = param_id(param);
+                quote! {
+                    descriptions.push((id(#id), String::from(#description)));
+                }
+            }),
+            RPItem::Nested(nested) => Some(nested_param_descriptions(
+                &nested.ident,
+                nested.array,
+                nested.id_prefix.as_ref(),
+            )),
+            RPItem::Persist(_) => None,
         })
-        .collect::<Vec<_>>()
-        .join(", \n    ");
+    }))?;
+    let descriptions = descriptions.into_iter().flatten();
+
+    let bindings_generator = if params.nested {
+        quote! {}
+    } else {
+        bindings_generator(ident, params.file_prefix.as_ref(), &params.migrated_ids)
+    };
+
+    Ok(quote! {
+        impl react_plug::bindings::ParamsBindings for #ident {
+            fn ts_type() -> String {
+                react_plug::bindings::ts_object(&[#(#type_fields),*])
+            }
 
-    let params_bindings = format!(
-        r#"import * as ReactPlug from "./react-plug.ts";
+            #[allow(unused_variables)]
+            fn ts_constructor(&self, id: &dyn Fn(&str) -> String) -> String {
+                react_plug::bindings::ts_object(&[#(#constructor_fields),*])
+            }
 
-export type Params = {{{{
-    {}
-}}}};
+            #[allow(unused_variables, unused_mut)]
+            fn param_descriptions(&self, id: &dyn Fn(&str) -> String) -> Vec<(String, String)> {
+                let mut descriptions = vec![];
+                #(#descriptions)*
+                descriptions
+            }
+        }
 
-export const createParameters: () => Params = () => ({{{{
-    {}
-}}}});
-"#,
-        param_type_def, param_defaults
-    );
+        #bindings_generator
+    })
+}
 
+/// Registers the bindings of a root parameters struct, so that `cargo xtask` can
+/// generate them using `react_plug::bindings::generate_bindings`.
+fn bindings_generator(
+    ident: &Ident,
+    file_prefix: Option<&LitStr>,
+    migrated_ids: &[LitStr],
+) -> proc_macro2::TokenStream {
     static LIBRARY_CODE: &str = include_str!("../ts/react-plug.ts");
 
     static PLUGIN_PROVIDER: &str = include_str!("../ts/PluginProvider.tsx");
 
+    let name = ident.to_string();
+    let file_prefix = file_prefix.map(LitStr::value).unwrap_or_default();
+
     quote! {
-        #[cfg(test)]
-        mod bindings {
-            use super::*;
-            use std::fs::{File, create_dir_all};
-            use std::env;
-            use std::path::Path;
-            use std::io::prelude::*;
-
-            #[test]
-            fn generate_bindings() {
-                let ts = format!(#params_bindings, #(#args),*);
-
-                let path = Path::new(&env::var("CARGO_MANIFEST_DIR").unwrap()).join("gui/src/bindings");
-                create_dir_all(&path).expect("Couldn't create directory for bindings");
-
-                let mut file = File::create(path.join("Params.ts")).unwrap();
-                file.write_all(ts.as_bytes()).unwrap();
-
-                let rp_ts = #LIBRARY_CODE;
-                let mut file = File::create(path.join("react-plug.ts")).unwrap();
-                file.write_all(rp_ts.as_bytes()).unwrap();
-
-                let plugin_provider = #PLUGIN_PROVIDER;
-                let mut file = File::create(path.join("PluginProvider.tsx")).unwrap();
-                file.write_all(plugin_provider.as_bytes()).unwrap();
+        react_plug::bindings::inventory::submit! {
+            react_plug::bindings::BindingsGenerator {
+                name: #name,
+                file_prefix: #file_prefix,
+                params_ts: react_plug::bindings::default_params_ts::<#ident>,
+                locked_params: react_plug::bindings::default_locked_params::<#ident>,
+                migrated_ids: &[#(#migrated_ids),*],
+                library: &[
+                    ("react-plug.ts", #LIBRARY_CODE),
+                    ("PluginProvider.tsx", #PLUGIN_PROVIDER),
+                ],
+            }
+        }
+    }
+}
+
+/// The TS expression that creates a field of the parameters object.
+fn ts_constructor_field(item: &RPItem) -> syn::Result<proc_macro2::TokenStream> {
+    match item {
+        RPItem::Param(param) => {
+            let (template, args) = param_to_ts(param)?;
+            Ok(quote! { format!(#template, #(#args),*) })
+        }
+        RPItem::Nested(nested) => Ok(nested_ts_constructor(
+            &nested.ident,
+            nested.array,
+            nested.id_prefix.as_ref(),
+        )),
+        RPItem::Persist(persist) => {
+            let name = persist.ident.to_string();
+            let key = persist.key();
+            let ty = &persist.ty;
+            let default_value = persist_default_value(persist);
+            Ok(quote! {
+                format!(
+                    "{}: new ReactPlug.PersistentField({{ key: {:?}, defaultValue: {} }})",
+                    #name,
+                    id(#key),
+                    react_plug::bindings::ts_value::<#ty>(&#default_value)
+                )
+            })
+        }
+    }
+}
+
+/// The TS expression that creates a nested parameters object, or an array of them.
+fn nested_ts_constructor(
+    ident: &Ident,
+    array: bool,
+    id_prefix: Option<&LitStr>,
+) -> proc_macro2::TokenStream {
+    let name = ident.to_string();
+    match (array, id_prefix) {
+        (true, _) => quote! {
+            format!("{}: {}", #name, react_plug::bindings::ts_array(
+                &self.#ident
+                    .iter()
+                    .enumerate()
+                    .map(|(index, params)| react_plug::bindings::ParamsBindings::ts_constructor(
+                        params,
+                        &|param_id| id(&react_plug::bindings::array_id(param_id, index))
+                    ))
+                    .collect::<Vec<_>>()
+            ))
+        },
+        (false, Some(id_prefix)) => quote! {
+            format!("{}: {}", #name, react_plug::bindings::ParamsBindings::ts_constructor(
+                &self.#ident,
+                &|param_id| id(&react_plug::bindings::prefixed_id(#id_prefix, param_id))
+            ))
+        },
+        (false, None) => quote! {
+            format!("{}: {}", #name, react_plug::bindings::ParamsBindings::ts_constructor(&self.#ident, id))
+        },
+    }
+}
+
+/// Adds the descriptions of a nested parameters struct, or an array of them, to
+/// `descriptions`, mapping their IDs like [nested_ts_constructor].
+fn nested_param_descriptions(
+    ident: &Ident,
+    array: bool,
+    id_prefix: Option<&LitStr>,
+) -> proc_macro2::TokenStream {
+    match (array, id_prefix) {
+        (true, _) => quote! {
+            for (index, params) in self.#ident.iter().enumerate() {
+                descriptions.extend(react_plug::bindings::ParamsBindings::param_descriptions(
+                    params,
+                    &|param_id| id(&react_plug::bindings::array_id(param_id, index))
+                ));
+            }
+        },
+        (false, Some(id_prefix)) => quote! {
+            descriptions.extend(react_plug::bindings::ParamsBindings::param_descriptions(
+                &self.#ident,
+                &|param_id| id(&react_plug::bindings::prefixed_id(#id_prefix, param_id))
+            ));
+        },
+        (false, None) => quote! {
+            descriptions.extend(react_plug::bindings::ParamsBindings::param_descriptions(&self.#ident, id));
+        },
+    }
+}
+
+fn persist_default_value(persist: &RPPersist) -> proc_macro2::TokenStream {
+    persist
+        .default_value
+        .as_ref()
+        .map(|expr| expr.to_token_stream())
+        .unwrap_or_else(|| quote! { Default::default() })
+}
+
+/// Returns a format string that creates the TS object of a parameter, and the
+/// arguments it needs. The parameter's ID is mapped by a function called `id`.
+fn param_to_ts(param: &RPParam) -> syn::Result<(String, Vec<proc_macro2::TokenStream>)> {
+    let mut args = vec![];
+    let param_ident = &param.ident.to_string();
+    let param_ty = &param.ty.to_token_stream().to_string();
+    let mut options = vec![];
+
+    for field in &param.fields {
+        match field.ident.to_string().as_str() {
+            "smoother" | "description" => {}
+            "range" => {
+                let (range_options, range_args) = range_to_ts(&field.expr, &param.ty)?;
+                options.push(format!("range: {}", range_options));
+                args.extend(range_args);
+            }
+            "value_to_string" => {
+                if let Expr::Call(call) = &field.expr {
+                    if call
+                        .func
+                        .to_token_stream()
+                        .to_string()
+                        .split("::")
+                        .next()
+                        .unwrap()
+                        .trim()
+                        == "formatters"
+                    {
+                        call.args
+                            .iter()
+                            .for_each(|arg| args.push(arg.to_token_stream()));
+                        options.push(format!(
+                            "value_to_string: ReactPlug.formatters.{}({})",
+                            call.func
+                                .to_token_stream()
+                                .to_string()
+                                .split("::")
+                                .last()
+                                .unwrap()
+                                .trim(),
+                            "{}".repeat(call.args.len())
+                        ));
+                    }
+                }
+            }
+            "string_to_value" => {
+                // TODO
+            }
+            "variants" => {
+                let ty = enum_type(&field.expr)?;
+                let idents = enum_variant_idents(&field.expr)?;
+                options.push("variants: {}".to_string());
+                args.push(quote! { react_plug::bindings::ts_enum_variants_object::<#ty>(#idents) });
+            }
+            "default_value" => {
+                options.push("defaultValue: {}".to_string());
+                if param.ty == RPParamType::EnumParam {
+                    let variants = find_field(param, "variants")?;
+                    let ty = enum_type(&variants)?;
+                    let idents = enum_variant_idents(&variants)?;
+                    let expr = &field.expr;
+                    args.push(quote! { react_plug::bindings::ts_enum_value(#ty::#expr, #idents) });
+                } else {
+                    args.push(field.expr.to_token_stream());
+                }
+            }
+            "flags" => {
+                options.push("flags: {}".to_string());
+                let expr = &field.expr;
+                args.push(quote! { react_plug::bindings::ts_flags(#expr) });
+            }
+            "id" => {
+                options.push("id: {:?}".to_string());
+                let expr = &field.expr;
+                args.push(quote! { id(#expr) });
+            }
+            ident => {
+                options.push(format!(
+                    "{}: {{:?}}",
+                    ident.to_string().to_lower_camel_case()
+                ));
+                args.push(field.expr.to_token_stream());
             }
         }
     }
+
+    if find_field(param, "id").is_err() {
+        options.push("id: {:?}".to_string());
+        args.push(quote! { id(#param_ident) });
+    }
+
+    if let Some(description) = param.description()? {
+        options.push("description: {:?}".to_string());
+        args.push(description.to_token_stream());
+    }
+
+    Ok((
+        format!(
+            "{}: new ReactPlug.parameters.{}({{{{ {} }}}})",
+            param_ident,
+            param_ty,
+            options.join(", ")
+        ),
+        args,
+    ))
 }
 
-fn range_to_ts(expr: &Expr, param_type: &RPParamType) -> (String, Vec<proc_macro2::TokenStream>) {
+fn range_to_ts(
+    expr: &Expr,
+    param_type: &RPParamType,
+) -> syn::Result<(String, Vec<proc_macro2::TokenStream>)> {
+    let (range_kind, range_types): (_, &[_]) = match param_type {
+        RPParamType::FloatParam => ("Float", &["Linear", "Skewed", "SymmetricalSkewed"]),
+        RPParamType::IntParam => ("Int", &["Linear"]),
+        _ => {
+            return Err(Error::new_spanned(
+                expr,
+                "This parameter type doesn't support ranges",
+            ))
+        }
+    };
+
     let mut args = vec![];
     match expr {
         Expr::Struct(structure) => {
-            let range_type = structure
-                .path
-                .segments
-                .last()
-                .unwrap()
-                .ident
-                .to_token_stream();
+            let range_type = &structure.path.segments.last().unwrap().ident;
+            if !range_types.iter().any(|ty| range_type == ty) {
+                return Err(Error::new(
+                    range_type.span(),
+                    format!(
+                        "Expected one of these {}Range variants: {}",
+                        range_kind,
+                        range_types.join(", ")
+                    ),
+                ));
+            }
 
             let constructor_args = structure
                 .fields
@@ -410,53 +771,50 @@ fn range_to_ts(expr: &Expr, param_type: &RPParamType) -> (String, Vec<proc_macro
                 .collect::<Vec<String>>()
                 .join(", ");
 
-            (
+            Ok((
                 format!(
                     "new ReactPlug.ranges.{}{}Range({{{{ {} }}}})",
-                    range_type,
-                    match param_type {
-                        RPParamType::FloatParam => "Float",
-                        RPParamType::IntParam => "Int",
-                        _ => {
-                            panic!("This parameter type doesn't support ranges.")
-                        }
-                    },
-                    constructor_args
+                    range_type, range_kind, constructor_args
                 ),
                 args,
-            )
+            ))
         }
         Expr::Call(call) => {
-            if let Expr::Path(path) = call.func.deref() {
-                if path.path.segments.last().unwrap().ident == "Reversed" {
-                    let mut expr = call.args.first().unwrap();
+            let is_reversed = matches!(
+                call.func.deref(),
+                Expr::Path(path) if path.path.segments.last().unwrap().ident == "Reversed"
+            );
+            if !is_reversed || call.args.len() != 1 {
+                return Err(Error::new_spanned(
+                    expr,
+                    format!(
+                        "Expected a range, e.g. `{}Range::Linear {{ min, max }}` or `{0}Range::Reversed(&...)`",
+                        range_kind
+                    ),
+                ));
+            }
 
-                    if let Expr::Reference(reference) = expr {
-                        expr = &*reference.expr;
-                    }
+            let mut expr = call.args.first().unwrap();
 
-                    let (inner_options, inner_args) = range_to_ts(&expr, param_type);
-                    (
-                        format!(
-                            "new ReactPlug.ranges.Reversed{}Range({})",
-                            match param_type {
-                                RPParamType::FloatParam => "Float",
-                                RPParamType::IntParam => "Int",
-                                _ => {
-                                    panic!("This parameter type doesn't support ranges.")
-                                }
-                            },
-                            inner_options
-                        ),
-                        inner_args,
-                    )
-                } else {
-                    panic!("No valid range provided!")
-                }
-            } else {
-                panic!("No valid range provided!")
+            if let Expr::Reference(reference) = expr {
+                expr = &*reference.expr;
             }
+
+            let (inner_options, inner_args) = range_to_ts(expr, param_type)?;
+            Ok((
+                format!(
+                    "new ReactPlug.ranges.Reversed{}Range({})",
+                    range_kind, inner_options
+                ),
+                inner_args,
+            ))
         }
-        _ => ("".to_string(), args),
+        _ => Err(Error::new_spanned(
+            expr,
+            format!(
+                "Ranges need to be written out, e.g. `{}Range::Linear {{ min, max }}`, so that they can be recreated in the GUI",
+                range_kind
+            ),
+        )),
     }
 }
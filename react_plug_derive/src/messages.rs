@@ -0,0 +1,274 @@
+use crate::collect_all;
+use heck::{ToLowerCamelCase, ToSnakeCase};
+use proc_macro2::{Ident, TokenStream};
+use quote::{format_ident, quote};
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
+use syn::{Attribute, Data, DeriveInput, Error, Fields, Token, Variant};
+
+/// A messages declaration. The `GuiMessage` enum holds the messages the GUI sends to
+/// the plugin, the `PluginMessage` enum those the plugin sends to the GUI. Either
+/// can be left out if there are no such messages.
+///
+/// ## Example
+///
+/// ```rust
+/// enum GuiMessage {
+///     Ping,
+///     SetTitle(String),
+///     Resize { width: u32, height: u32 },
+/// }
+///
+/// enum PluginMessage {
+///     Pong,
+/// }
+/// ```
+pub struct RPMessages {
+    pub gui: Option<DeriveInput>,
+    pub plugin: Option<DeriveInput>,
+}
+
+impl Parse for RPMessages {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut gui = None;
+        let mut plugin = None;
+
+        while !input.is_empty() {
+            let item: DeriveInput = input.parse()?;
+            if !matches!(item.data, Data::Enum(_)) {
+                return Err(Error::new(item.ident.span(), "Messages need to be enums"));
+            }
+
+            let messages = if item.ident == "GuiMessage" {
+                &mut gui
+            } else if item.ident == "PluginMessage" {
+                &mut plugin
+            } else {
+                return Err(Error::new(
+                    item.ident.span(),
+                    "Expected `GuiMessage` or `PluginMessage`",
+                ));
+            };
+
+            if messages.is_some() {
+                return Err(Error::new(
+                    item.ident.span(),
+                    format!("`{}` is declared more than once", item.ident),
+                ));
+            }
+
+            if !item.generics.params.is_empty() {
+                return Err(Error::new(
+                    item.generics.span(),
+                    "Message enums can't be generic",
+                ));
+            }
+
+            collect_all(
+                std::iter::once(&item.attrs)
+                    .chain(variants(&item).iter().map(|variant| &variant.attrs))
+                    .map(|attrs| no_serde_attrs(attrs)),
+            )?;
+
+            *messages = Some(item);
+        }
+
+        Ok(Self { gui, plugin })
+    }
+}
+
+fn variants(item: &DeriveInput) -> &Punctuated<Variant, Token![,]> {
+    match &item.data {
+        Data::Enum(data) => &data.variants,
+        _ => unreachable!("Messages are checked to be enums when they're parsed"),
+    }
+}
+
+/// The GUI relies on serde's default representation of the enums, so it can't be
+/// changed.
+fn no_serde_attrs(attrs: &[Attribute]) -> syn::Result<()> {
+    match attrs.iter().find(|attr| attr.path().is_ident("serde")) {
+        Some(attr) => Err(Error::new_spanned(
+            attr,
+            "Messages can't have #[serde(...)] attributes, the GUI relies on their default representation",
+        )),
+        None => Ok(()),
+    }
+}
+
+pub fn expand_messages(messages: &RPMessages) -> TokenStream {
+    let gui = messages
+        .gui
+        .clone()
+        .unwrap_or_else(|| syn::parse_quote! { pub enum GuiMessage {} });
+    let plugin = messages
+        .plugin
+        .clone()
+        .unwrap_or_else(|| syn::parse_quote! { pub enum PluginMessage {} });
+
+    let handler = message_handler(&gui);
+    let messages_ts = messages_ts(&gui, &plugin);
+
+    quote! {
+        #[derive(Debug, serde::Serialize, serde::Deserialize, ts_rs::TS)]
+        #[ts(export_to = "GuiMessage.ts")]
+        #gui
+
+        #[derive(Debug, serde::Serialize, serde::Deserialize, ts_rs::TS)]
+        #[ts(export_to = "PluginMessage.ts")]
+        #plugin
+
+        #handler
+
+        react_plug::bindings::inventory::submit! {
+            react_plug::bindings::MessagesBindings {
+                export: |dir| {
+                    <GuiMessage as react_plug::bindings::TS>::export_all_to(dir)?;
+                    <PluginMessage as react_plug::bindings::TS>::export_all_to(dir)
+                },
+                messages_ts: #messages_ts,
+            }
+        }
+    }
+}
+
+/// The `GuiMessageHandler` trait with a method for each GUI message, and the
+/// implementation of `DispatchMessage` that calls them.
+fn message_handler(gui: &DeriveInput) -> TokenStream {
+    let vis = &gui.vis;
+    let (methods, arms): (Vec<_>, Vec<_>) = variants(gui)
+        .iter()
+        .map(|variant| {
+            let ident = &variant.ident;
+            let method = format_ident!("{}", ident.to_string().to_snake_case());
+            let docs = variant
+                .attrs
+                .iter()
+                .filter(|attr| attr.path().is_ident("doc"));
+            let (args, pattern) = variant_args(variant);
+            let params = variant.fields.iter().zip(&args).map(|(field, arg)| {
+                let ty = &field.ty;
+                quote! { #arg: #ty }
+            });
+
+            (
+                quote! {
+                    #(#docs)*
+                    fn #method(&self, #(#params,)* send: &react_plug::editor::PluginMessageSender<PluginMessage>);
+                },
+                quote! {
+                    GuiMessage::#ident #pattern => handler.#method(#(#args,)* send)
+                },
+            )
+        })
+        .unzip();
+
+    quote! {
+        /// Handles the messages the GUI sends, with a method for each [GuiMessage].
+        /// Pass it to `ReactPlugEditor::with_handler`.
+        #vis trait GuiMessageHandler {
+            #(#methods)*
+        }
+
+        impl<H: GuiMessageHandler> react_plug::editor::DispatchMessage<PluginMessage, H> for GuiMessage {
+            #[allow(unused_variables)]
+            fn dispatch(self, handler: &H, send: &react_plug::editor::PluginMessageSender<PluginMessage>) {
+                match self {
+                    #(#arms),*
+                }
+            }
+        }
+    }
+}
+
+/// The names the fields of a variant are bound to, and the pattern that binds them.
+fn variant_args(variant: &Variant) -> (Vec<Ident>, TokenStream) {
+    match &variant.fields {
+        Fields::Named(fields) => {
+            let args = fields
+                .named
+                .iter()
+                .map(|field| field.ident.clone().unwrap())
+                .collect::<Vec<_>>();
+            let pattern = quote! { { #(#args),* } };
+            (args, pattern)
+        }
+        Fields::Unnamed(fields) => {
+            let args = if fields.unnamed.len() == 1 {
+                vec![format_ident!("value")]
+            } else {
+                (0..fields.unnamed.len())
+                    .map(|index| format_ident!("value_{}", index))
+                    .collect()
+            };
+            let pattern = quote! { ( #(#args),* ) };
+            (args, pattern)
+        }
+        Fields::Unit => (vec![], quote! {}),
+    }
+}
+
+/// Generates `Messages.ts`, with a function that sends each GUI message and one that
+/// listens to each plugin message.
+fn messages_ts(gui: &DeriveInput, plugin: &DeriveInput) -> String {
+    let send = variants(gui)
+        .iter()
+        .map(|variant| {
+            let name = variant.ident.to_string();
+            match variant.fields {
+                Fields::Unit => format!(
+                    "  {}: () => ReactPlug.sendToPlugin<GuiMessage>({{Message: {:?}}}),\n",
+                    name.to_lower_camel_case(),
+                    name
+                ),
+                _ => format!(
+                    "  {}: (payload: ReactPlug.MessagePayload<GuiMessage, {:?}>) => ReactPlug.sendToPlugin<GuiMessage>({{Message: {{{}: payload}}}}),\n",
+                    name.to_lower_camel_case(),
+                    name,
+                    name
+                ),
+            }
+        })
+        .collect::<String>();
+
+    let listen = variants(plugin)
+        .iter()
+        .map(|variant| {
+            let name = variant.ident.to_string();
+            match variant.fields {
+                Fields::Unit => format!(
+                    "  {}: (ctx: ReactPlug.MessageListeners<PluginMessage>, listener: () => void) => ReactPlug.onMessage(ctx, {:?}, () => listener()),\n",
+                    name.to_lower_camel_case(),
+                    name
+                ),
+                _ => format!(
+                    "  {}: (ctx: ReactPlug.MessageListeners<PluginMessage>, listener: (payload: ReactPlug.MessagePayload<PluginMessage, {:?}>) => void) => ReactPlug.onMessage(ctx, {:?}, listener),\n",
+                    name.to_lower_camel_case(),
+                    name,
+                    name
+                ),
+            }
+        })
+        .collect::<String>();
+
+    format!(
+        r#"import {{type GuiMessage}} from "./GuiMessage.ts";
+import {{type PluginMessage}} from "./PluginMessage.ts";
+import * as ReactPlug from "./react-plug.ts";
+
+/** Sends a message to the plugin, with a function for each `GuiMessage`. */
+export const sendMessage = {{
+{}}};
+
+/**
+ * Listens to the messages of the plugin, with a function for each `PluginMessage`.
+ * Each returns a function that removes the listener, e.g.
+ * `useEffect(() => onMessage.pong(ctx, () => ...), [ctx])`.
+ */
+export const onMessage = {{
+{}}};
+"#,
+        send, listen
+    )
+}
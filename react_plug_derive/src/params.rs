@@ -3,7 +3,9 @@ use quote::{quote, ToTokens};
 use syn::parse::{Parse, ParseStream};
 use syn::punctuated::Punctuated;
 use syn::spanned::Spanned;
-use syn::{braced, token, Error, Expr, Token, Type};
+use syn::{
+    braced, bracketed, token, Attribute, Error, Expr, LitStr, Path, Token, Type, Visibility,
+};
 
 /// A Params declaration. An identifier, followed by a braced declaration of all
 /// parameters as [RPParams](RPParam).
@@ -33,32 +35,376 @@ use syn::{braced, token, Error, Expr, Token, Type};
 ///         name: "Int Test",
 ///         value: 0,
 ///         range: IntRange::Linear { min: 0, max: 10 }
-///     }
+///     },
+///     #[nested(array, group = "Band")]
+///     bands: [BandParams; 4]
 /// }
 /// ```
+///
+/// Structs that are only used as nested parameter structs are marked with
+/// `#[nested]`, so that they don't generate their own `Params.ts`. Other structs can
+/// be given a prefix for their bindings' file names using
+/// `#[bindings(file_prefix = "...")]`, and the IDs of parameters they migrate using
+/// `#[bindings(migrated_ids = ["..."])]`. All other attributes, and the struct's
+/// visibility, are forwarded to the generated struct.
 pub struct RPParams {
+    pub attrs: Vec<Attribute>,
+    pub vis: Visibility,
+    pub nested: bool,
+    pub file_prefix: Option<LitStr>,
+    pub migrated_ids: Vec<LitStr>,
     pub ident: Ident,
     pub brace_token: token::Brace,
-    pub params: Punctuated<RPParam, Token![,]>,
+    pub items: Punctuated<RPItem, Token![,]>,
+}
+
+impl RPParams {
+    pub fn params(&self) -> impl Iterator<Item = &RPParam> {
+        self.items.iter().filter_map(|item| match item {
+            RPItem::Param(param) => Some(param),
+            _ => None,
+        })
+    }
 }
 
 impl Parse for RPParams {
     fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut attrs = vec![];
+        let mut nested = false;
+        let mut file_prefix = None;
+        let mut migrated_ids = vec![];
+        for attr in input.call(Attribute::parse_outer)? {
+            if attr.path().is_ident("nested") {
+                attr.meta.require_path_only()?;
+                nested = true;
+            } else if attr.path().is_ident("bindings") {
+                attr.parse_nested_meta(|meta| {
+                    if meta.path.is_ident("file_prefix") {
+                        file_prefix = Some(meta.value()?.parse()?);
+                        Ok(())
+                    } else if meta.path.is_ident("migrated_ids") {
+                        migrated_ids = parse_lit_str_array(meta.value()?)?;
+                        Ok(())
+                    } else {
+                        Err(meta.error("Expected `file_prefix` or `migrated_ids`"))
+                    }
+                })?;
+            } else {
+                attrs.push(attr);
+            }
+        }
+
         let content;
-        Ok(Self {
+        let params = Self {
+            attrs,
+            vis: input.parse()?,
+            nested,
+            file_prefix,
+            migrated_ids,
             ident: input.parse()?,
             brace_token: braced!(content in input),
-            params: content.parse_terminated(RPParam::parse, Token![,])?,
+            items: content.parse_terminated(RPItem::parse, Token![,])?,
+        };
+
+        if let Some(second) = params.params().filter(|param| param.is_bypass()).nth(1) {
+            return Err(Error::new(
+                second.ident.span(),
+                "Only a single parameter can be marked as a bypass parameter",
+            ));
+        }
+
+        Ok(params)
+    }
+}
+
+mod kw {
+    syn::custom_keyword!(Persist);
+}
+
+/// An entry of a Params declaration, either a [RPParam], a [RPNested] struct, or a
+/// [RPPersist] field.
+pub enum RPItem {
+    Param(RPParam),
+    Nested(RPNested),
+    Persist(RPPersist),
+}
+
+impl Parse for RPItem {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let fork = input.fork();
+        let attrs = fork.call(Attribute::parse_outer)?;
+        if attrs.iter().any(|attr| attr.path().is_ident("nested")) {
+            return Ok(RPItem::Nested(input.parse()?));
+        }
+
+        fork.parse::<Visibility>()?;
+        let is_persist = fork.parse::<Ident>().is_ok()
+            && fork.parse::<Token![:]>().is_ok()
+            && fork.peek(kw::Persist);
+
+        if is_persist {
+            Ok(RPItem::Persist(input.parse()?))
+        } else {
+            Ok(RPItem::Param(input.parse()?))
+        }
+    }
+}
+
+/// A persisted field that isn't a parameter, using nih-plug's `#[persist]`
+/// attribute. The type needs to implement `Serialize`, `Deserialize` and ts-rs'
+/// `TS`. Both the key and the default value are optional, the key defaults to the
+/// field's identifier. Attributes and the visibility are forwarded to the struct's
+/// field.
+///
+/// ## Example
+///
+/// ```rust
+/// /// The sample that was loaded last.
+/// sample_path: Persist<Option<String>> {
+///     key: "sample-path",
+///     default_value: None,
+/// }
+/// ```
+pub struct RPPersist {
+    pub attrs: Vec<Attribute>,
+    pub vis: Visibility,
+    pub ident: Ident,
+    pub colon_token: Token![:],
+    pub persist_token: kw::Persist,
+    pub lt_token: Token![<],
+    pub ty: Type,
+    pub gt_token: Token![>],
+    pub key: Option<LitStr>,
+    pub default_value: Option<Expr>,
+}
+
+impl RPPersist {
+    pub fn key(&self) -> LitStr {
+        self.key
+            .clone()
+            .unwrap_or_else(|| LitStr::new(&self.ident.to_string(), self.ident.span()))
+    }
+}
+
+impl Parse for RPPersist {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let attrs = input.call(Attribute::parse_outer)?;
+        no_generated_attrs(&attrs)?;
+        let vis = input.parse()?;
+        let ident = input.parse()?;
+        let colon_token = input.parse()?;
+        let persist_token = input.parse()?;
+        let lt_token = input.parse()?;
+        let ty = input.parse()?;
+        let gt_token = input.parse()?;
+
+        let mut key = None;
+        let mut default_value = None;
+        if input.peek(token::Brace) {
+            let content;
+            braced!(content in input);
+
+            for field in content.parse_terminated(RPParamField::parse, Token![,])? {
+                match field.ident.to_string().as_str() {
+                    "key" => {
+                        key = Some(syn::parse2::<LitStr>(field.expr.to_token_stream())?);
+                    }
+                    "default_value" => default_value = Some(field.expr),
+                    _ => {
+                        return Err(Error::new(
+                            field.ident.span(),
+                            "Expected `key` or `default_value`",
+                        ))
+                    }
+                }
+            }
+        }
+
+        Ok(Self {
+            attrs,
+            vis,
+            ident,
+            colon_token,
+            persist_token,
+            lt_token,
+            ty,
+            gt_token,
+            key,
+            default_value,
+        })
+    }
+}
+
+/// A nested parameter struct, using nih-plug's `#[nested]` attribute. The struct
+/// needs to implement `ParamsBindings`, e.g. by being declared using
+/// `define_params!` as well. Other attributes and the visibility are forwarded to
+/// the struct's field.
+///
+/// ## Examples
+///
+/// ```rust
+/// #[nested(group = "Filter")]
+/// filter: FilterParams
+/// ```
+///
+/// ```rust
+/// #[nested(id_prefix = "osc1", group = "Oscillator 1")]
+/// osc1: OscillatorParams
+/// ```
+///
+/// ```rust
+/// #[nested(array, group = "Band")]
+/// bands: [BandParams; 4]
+/// ```
+pub struct RPNested {
+    pub attrs: Vec<Attribute>,
+    pub attr: Attribute,
+    pub id_prefix: Option<LitStr>,
+    pub array: bool,
+    pub vis: Visibility,
+    pub ident: Ident,
+    pub colon_token: Token![:],
+    pub ty: Type,
+}
+
+impl Parse for RPNested {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let (nested, attrs): (Vec<_>, Vec<_>) = input
+            .call(Attribute::parse_outer)?
+            .into_iter()
+            .partition(|attr| attr.path().is_ident("nested"));
+        let attr = match nested.as_slice() {
+            [attr] => attr.clone(),
+            _ => {
+                return Err(Error::new(
+                    nested[1].span(),
+                    "Expected a single #[nested(...)] attribute",
+                ))
+            }
+        };
+        no_generated_attrs(&attrs)?;
+
+        let (id_prefix, array) = parse_nested_attr(&attr)?;
+
+        let vis = input.parse()?;
+        let ident = input.parse()?;
+        let colon_token = input.parse()?;
+        let ty: Type = input.parse()?;
+
+        if array && !matches!(ty, Type::Array(_)) {
+            return Err(Error::new(
+                ty.span(),
+                "Nested parameter arrays need to be fixed-size arrays, e.g. [BandParams; 4]",
+            ));
+        }
+
+        Ok(Self {
+            attrs,
+            attr,
+            id_prefix,
+            array,
+            vis,
+            ident,
+            colon_token,
+            ty,
+        })
+    }
+}
+
+/// Parses an array of string literals, like `["gain", "cutoff"]`.
+pub fn parse_lit_str_array(input: ParseStream) -> syn::Result<Vec<LitStr>> {
+    let content;
+    bracketed!(content in input);
+
+    Ok(Punctuated::<LitStr, Token![,]>::parse_terminated(&content)?
+        .into_iter()
+        .collect())
+}
+
+/// Checks that an item's attributes don't include the ones `define_params!`
+/// generates itself.
+fn no_generated_attrs(attrs: &[Attribute]) -> syn::Result<()> {
+    match attrs
+        .iter()
+        .find(|attr| attr.path().is_ident("id") || attr.path().is_ident("persist"))
+    {
+        Some(attr) => Err(Error::new_spanned(
+            attr,
+            "#[id] and #[persist] are generated by define_params!, use the `id` field or the `key` of a Persist field instead",
+        )),
+        None => Ok(()),
+    }
+}
+
+/// The text of the doc comments in `attrs`, with the space after each `///`
+/// removed, or `None` if there are none.
+pub fn doc_comment(attrs: &[Attribute]) -> Option<LitStr> {
+    let docs = attrs
+        .iter()
+        .filter_map(|attr| match &attr.meta {
+            syn::Meta::NameValue(meta) if meta.path.is_ident("doc") => match &meta.value {
+                Expr::Lit(syn::ExprLit {
+                    lit: syn::Lit::Str(doc),
+                    ..
+                }) => Some((attr, doc.value())),
+                _ => None,
+            },
+            _ => None,
         })
+        .collect::<Vec<_>>();
+
+    let text = docs
+        .iter()
+        .map(|(_, line)| line.strip_prefix(' ').unwrap_or(line))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let text = text.trim();
+
+    match docs.first() {
+        Some((attr, _)) if !text.is_empty() => Some(LitStr::new(text, attr.span())),
+        _ => None,
+    }
+}
+
+/// Parses the `id_prefix` and whether the struct is nested as an `array` from
+/// nih-plug's `#[nested(...)]` attribute.
+pub fn parse_nested_attr(attr: &Attribute) -> syn::Result<(Option<LitStr>, bool)> {
+    let mut id_prefix = None;
+    let mut array = false;
+    if !matches!(attr.meta, syn::Meta::Path(_)) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("array") {
+                array = true;
+            } else if meta.path.is_ident("id_prefix") {
+                id_prefix = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("group") {
+                meta.value()?.parse::<LitStr>()?;
+            } else {
+                return Err(meta.error("Expected `array`, `id_prefix` or `group`"));
+            }
+            Ok(())
+        })?;
+    }
+
+    if array && id_prefix.is_some() {
+        return Err(Error::new(
+            attr.span(),
+            "`array` and `id_prefix` can't be combined",
+        ));
     }
+
+    Ok((id_prefix, array))
 }
 
 /// A single parameter declaration. An identifier, a colon, a [RPParamType], and a
-/// braced declaration of [RPParamFields](RPParamField).
+/// braced declaration of [RPParamFields](RPParamField). It can be documented
+/// using doc comments, or using a `description` field instead. Other attributes and
+/// the visibility are forwarded to the struct's field.
 ///
 /// ## Example
 ///
 /// ```rust
+/// /// The output gain, applied after all other processing.
 /// gain: FloatParam {
 ///     name: "Gain",
 ///     value: util::db_to_gain(0.0),
@@ -74,6 +420,8 @@ impl Parse for RPParams {
 /// }
 /// ```
 pub struct RPParam {
+    pub attrs: Vec<Attribute>,
+    pub vis: Visibility,
     pub ident: Ident,
     pub colon_token: Token![:],
     pub ty: RPParamType,
@@ -81,17 +429,63 @@ pub struct RPParam {
     pub fields: Punctuated<RPParamField, Token![,]>,
 }
 
+impl RPParam {
+    /// Whether the parameter's `flags` include `ParamFlags::BYPASS`.
+    pub fn is_bypass(&self) -> bool {
+        self.fields
+            .iter()
+            .filter(|field| field.ident == "flags")
+            .flat_map(|field| field.expr.to_token_stream())
+            .any(|token| matches!(token, proc_macro2::TokenTree::Ident(ident) if ident == "BYPASS"))
+    }
+
+    /// The parameter's description, either its `description` field or its doc
+    /// comments.
+    pub fn description(&self) -> syn::Result<Option<LitStr>> {
+        match self
+            .fields
+            .iter()
+            .find(|field| field.ident == "description")
+        {
+            Some(field) => match &field.expr {
+                Expr::Lit(syn::ExprLit {
+                    lit: syn::Lit::Str(description),
+                    ..
+                }) => Ok(Some(description.clone())),
+                expr => Err(Error::new_spanned(
+                    expr,
+                    "The description needs to be a string literal",
+                )),
+            },
+            None => Ok(doc_comment(&self.attrs)),
+        }
+    }
+}
+
 impl Parse for RPParam {
     fn parse(input: ParseStream) -> syn::Result<Self> {
         let content;
+        let attrs = input.call(Attribute::parse_outer)?;
+        no_generated_attrs(&attrs)?;
 
-        Ok(Self {
+        let param = Self {
+            attrs,
+            vis: input.parse()?,
             ident: input.parse()?,
             colon_token: input.parse()?,
             ty: input.parse()?,
             brace_token: braced!(content in input),
             fields: content.parse_terminated(RPParamField::parse, Token![,])?,
-        })
+        };
+
+        if param.is_bypass() && param.ty != RPParamType::BoolParam {
+            return Err(Error::new(
+                param.ident.span(),
+                "Only a BoolParam can be marked as a bypass parameter",
+            ));
+        }
+
+        Ok(param)
     }
 }
 
@@ -119,14 +513,96 @@ pub struct RPParamField {
 
 impl Parse for RPParamField {
     fn parse(input: ParseStream) -> syn::Result<Self> {
+        let ident: Ident = input.parse()?;
+        let colon_token = input.parse()?;
+
+        // The variants of an enum aren't a valid expression, so they are kept as
+        // tokens and parsed as [RPEnumVariants] later on.
+        let fork = input.fork();
+        let expr = if ident == "variants" && fork.parse::<Path>().is_ok() && fork.peek(token::Brace)
+        {
+            let path: Path = input.parse()?;
+            let variants: proc_macro2::Group = input.parse()?;
+            Expr::Verbatim(quote! { #path #variants })
+        } else {
+            input.parse()?
+        };
+
         Ok(Self {
-            ident: input.parse()?,
-            colon_token: input.parse()?,
-            expr: input.parse()?,
+            ident,
+            colon_token,
+            expr,
+        })
+    }
+}
+
+/// The variants of an enum parameter, declared along with the enum's name. Each
+/// variant can be given a name and a stable ID, which become nih-plug's `#[name]`
+/// and `#[id]` attributes. Variants without a name use their identifier.
+///
+/// ## Example
+///
+/// ```rust
+/// Waveform {
+///     Sine: { name: "Sine Wave", id: "sine" },
+///     Square: { name: "Square Wave", id: "square" },
+///     Dirac: { id: "dirac" }
+/// }
+/// ```
+pub struct RPEnumVariants {
+    pub path: Path,
+    pub brace_token: token::Brace,
+    pub variants: Punctuated<RPEnumVariant, Token![,]>,
+}
+
+impl Parse for RPEnumVariants {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let content;
+
+        Ok(Self {
+            path: input.parse()?,
+            brace_token: braced!(content in input),
+            variants: content.parse_terminated(RPEnumVariant::parse, Token![,])?,
         })
     }
 }
 
+pub struct RPEnumVariant {
+    pub ident: Ident,
+    pub name: Option<LitStr>,
+    pub id: Option<LitStr>,
+}
+
+impl Parse for RPEnumVariant {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let ident = input.parse()?;
+        let mut name = None;
+        let mut id = None;
+
+        if input.parse::<Option<Token![:]>>()?.is_some() {
+            if input.peek(token::Brace) {
+                let content;
+                braced!(content in input);
+
+                for field in content.parse_terminated(RPParamField::parse, Token![,])? {
+                    let value = syn::parse2::<LitStr>(field.expr.to_token_stream())?;
+                    match field.ident.to_string().as_str() {
+                        "name" => name = Some(value),
+                        "id" => id = Some(value),
+                        _ => return Err(Error::new(field.ident.span(), "Expected `name` or `id`")),
+                    }
+                }
+            } else if input.peek(LitStr) {
+                name = Some(input.parse()?);
+            } else {
+                return Err(input.error("Expected the name of the variant as a string literal"));
+            }
+        }
+
+        Ok(Self { ident, name, id })
+    }
+}
+
 /// The types of parameters.
 #[derive(Debug, PartialEq, Eq)]
 pub enum RPParamType {
@@ -136,6 +612,51 @@ pub enum RPParamType {
     EnumParam,
 }
 
+impl RPParamType {
+    /// The fields that are passed to the parameter's constructor. These are
+    /// required.
+    pub fn constructor_fields(&self) -> &'static [&'static str] {
+        match self {
+            RPParamType::FloatParam | RPParamType::IntParam => &["name", "default_value", "range"],
+            RPParamType::BoolParam => &["name", "default_value"],
+            RPParamType::EnumParam => &["name", "default_value", "variants"],
+        }
+    }
+
+    /// The optional fields that are set using the parameter's `with_*()` methods.
+    pub fn modifier_fields(&self) -> &'static [&'static str] {
+        match self {
+            RPParamType::FloatParam => &[
+                "callback",
+                "flags",
+                "poly_modulation_id",
+                "smoother",
+                "step_size",
+                "string_to_value",
+                "unit",
+                "value_to_string",
+            ],
+            RPParamType::IntParam => &[
+                "callback",
+                "flags",
+                "poly_modulation_id",
+                "smoother",
+                "string_to_value",
+                "unit",
+                "value_to_string",
+            ],
+            RPParamType::BoolParam => &[
+                "callback",
+                "flags",
+                "poly_modulation_id",
+                "string_to_value",
+                "value_to_string",
+            ],
+            RPParamType::EnumParam => &["callback", "flags", "poly_modulation_id"],
+        }
+    }
+}
+
 impl ToTokens for RPParamType {
     fn to_tokens(&self, tokens: &mut TokenStream) {
         match self {
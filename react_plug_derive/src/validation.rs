@@ -0,0 +1,201 @@
+use crate::params::{RPItem, RPParam, RPParams};
+use crate::{collect_all, enum_variants, find_field};
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use std::collections::HashMap;
+use std::ops::Deref;
+use syn::{Error, Expr, ExprLit, ExprUnary, Lit, UnOp};
+
+/// Checks that the IDs of all parameters, and the keys of all persisted fields, are
+/// unique within the struct.
+///
+/// The length of IDs isn't checked, since VST3 and CLAP don't limit it: nih-plug
+/// hashes the IDs to the 32-bit integers both formats identify parameters by, and
+/// only stores the strings themselves in the plugin's state.
+pub fn validate_ids(params: &RPParams) -> syn::Result<()> {
+    let mut ids = HashMap::new();
+    let mut keys = HashMap::new();
+
+    collect_all(params.items.iter().map(|item| match item {
+        RPItem::Param(param) => match param_id(param) {
+            Some((id, span)) => match ids.insert(id.clone(), param.ident.to_string()) {
+                Some(other) => Err(Error::new(
+                    span,
+                    format!(
+                        "Duplicate parameter ID `{}`, it's already used by `{}`",
+                        id, other
+                    ),
+                )),
+                None => Ok(()),
+            },
+            None => Ok(()),
+        },
+        RPItem::Persist(persist) => {
+            let key = persist.key();
+            match keys.insert(key.value(), persist.ident.to_string()) {
+                Some(other) => Err(Error::new(
+                    key.span(),
+                    format!(
+                        "Duplicate persist key `{}`, it's already used by `{}`",
+                        key.value(),
+                        other
+                    ),
+                )),
+                None => Ok(()),
+            }
+        }
+        RPItem::Nested(_) => Ok(()),
+    }))
+    .map(drop)
+}
+
+/// Checks the range and default value of a parameter, as far as they are
+/// literals. Everything else is checked at runtime using [debug_assertions].
+pub fn validate_values(param: &RPParam) -> syn::Result<()> {
+    let mut results: Vec<syn::Result<()>> = vec![];
+
+    let range = find_field(param, "range").ok();
+    let bounds = range.as_ref().and_then(range_bounds);
+    let default_value = find_field(param, "default_value").ok();
+
+    if let (Some(range), Some((Some(min), Some(max)))) = (
+        &range,
+        bounds.map(|(min, max)| (literal_number(min), literal_number(max))),
+    ) {
+        if min >= max {
+            results.push(Err(Error::new_spanned(
+                range,
+                "The range's `min` needs to be less than its `max`",
+            )));
+        } else if let Some(value) = default_value.as_ref().and_then(literal_number) {
+            if value < min || value > max {
+                results.push(Err(Error::new_spanned(
+                    default_value.as_ref().unwrap(),
+                    format!(
+                        "The default value needs to be within the range, from {} to {}",
+                        min, max
+                    ),
+                )));
+            }
+        }
+    }
+
+    let variants = find_field(param, "variants")
+        .ok()
+        .and_then(|variants| enum_variants(&variants).ok());
+
+    if let (Some(Expr::Path(path)), Some(variants)) = (&default_value, variants) {
+        let is_variant = variants
+            .variants
+            .iter()
+            .any(|variant| path.path.is_ident(&variant.ident));
+
+        if !is_variant {
+            let enum_path = &variants.path;
+            results.push(Err(Error::new_spanned(
+                path,
+                format!(
+                    "The default value needs to be one of the variants of `{}`",
+                    quote!(#enum_path)
+                ),
+            )));
+        }
+    }
+
+    collect_all(results).map(drop)
+}
+
+/// Debug assertions for everything [validate_values] can't check at compile time,
+/// because it's computed. These are run when the parameters are created.
+pub fn debug_assertions(param: &RPParam) -> TokenStream {
+    let ident = param.ident.to_string();
+    let mut assertions = vec![];
+
+    let range = find_field(param, "range").ok();
+    if let Some((min, max)) = range.as_ref().and_then(range_bounds) {
+        let is_literal = literal_number(min).is_some() && literal_number(max).is_some();
+        let default_value = find_field(param, "default_value").ok();
+
+        if !is_literal {
+            assertions.push(quote! {
+                debug_assert!(
+                    #min < #max,
+                    "The range of `{}` needs a `min` that's less than its `max`",
+                    #ident
+                );
+            });
+        }
+
+        if let Some(default_value) = default_value {
+            if !is_literal || literal_number(&default_value).is_none() {
+                assertions.push(quote! {
+                    debug_assert!(
+                        (#min..=#max).contains(&(#default_value)),
+                        "The default value of `{}` needs to be within its range",
+                        #ident
+                    );
+                });
+            }
+        }
+    }
+
+    quote! { #(#assertions)* }
+}
+
+/// The ID of a parameter and where it's declared. IDs need to be string literals,
+/// anything else is reported by nih-plug's `#[id]` attribute.
+fn param_id(param: &RPParam) -> Option<(String, Span)> {
+    match param.fields.iter().find(|field| field.ident == "id") {
+        Some(field) => match &field.expr {
+            Expr::Lit(ExprLit {
+                lit: Lit::Str(id), ..
+            }) => Some((id.value(), id.span())),
+            _ => None,
+        },
+        None => Some((param.ident.to_string(), param.ident.span())),
+    }
+}
+
+/// The `min` and `max` of a range, looking through `Reversed` ranges.
+fn range_bounds(expr: &Expr) -> Option<(&Expr, &Expr)> {
+    match expr {
+        Expr::Struct(structure) => {
+            let field = |name: &str| {
+                structure
+                    .fields
+                    .iter()
+                    .find(
+                        |field| matches!(&field.member, syn::Member::Named(ident) if ident == name),
+                    )
+                    .map(|field| &field.expr)
+            };
+
+            Some((field("min")?, field("max")?))
+        }
+        Expr::Call(call) if call.args.len() == 1 => match call.args.first()? {
+            Expr::Reference(reference) => range_bounds(reference.expr.deref()),
+            expr => range_bounds(expr),
+        },
+        _ => None,
+    }
+}
+
+/// The value of a number literal, including negative ones.
+fn literal_number(expr: &Expr) -> Option<f64> {
+    match expr {
+        Expr::Lit(ExprLit {
+            lit: Lit::Float(lit),
+            ..
+        }) => lit.base10_parse().ok(),
+        Expr::Lit(ExprLit {
+            lit: Lit::Int(lit), ..
+        }) => lit.base10_parse().ok(),
+        Expr::Unary(ExprUnary {
+            op: UnOp::Neg(_),
+            expr,
+            ..
+        }) => literal_number(expr).map(|value| -value),
+        Expr::Paren(paren) => literal_number(&paren.expr),
+        _ => None,
+    }
+}
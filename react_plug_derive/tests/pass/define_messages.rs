@@ -0,0 +1,62 @@
+use react_plug::editor::{DispatchMessage, PluginMessageSender};
+use std::sync::Arc;
+
+react_plug::define_messages! {
+    enum GuiMessage {
+        Ping,
+        SetTitle(String),
+        Resize { width: u32, height: u32 },
+    }
+
+    enum PluginMessage {
+        Pong,
+        Title(String),
+        Area(u32),
+    }
+}
+
+struct Handler;
+
+impl GuiMessageHandler for Handler {
+    fn ping(&self, send: &PluginMessageSender<PluginMessage>) {
+        let _ = send(PluginMessage::Pong);
+    }
+
+    fn set_title(&self, value: String, send: &PluginMessageSender<PluginMessage>) {
+        let _ = send(PluginMessage::Title(value));
+    }
+
+    fn resize(&self, width: u32, height: u32, send: &PluginMessageSender<PluginMessage>) {
+        let _ = send(PluginMessage::Area(width * height));
+    }
+}
+
+fn main() {
+    let (sender, receiver) = crossbeam_channel::unbounded();
+    let send: PluginMessageSender<PluginMessage> =
+        Arc::new(move |message| sender.try_send(react_plug::PluginMessage::Message(message)));
+
+    GuiMessage::Ping.dispatch(&Handler, &send);
+    GuiMessage::SetTitle(String::from("Synth")).dispatch(&Handler, &send);
+    GuiMessage::Resize {
+        width: 4,
+        height: 3,
+    }
+    .dispatch(&Handler, &send);
+
+    let responses = receiver
+        .try_iter()
+        .map(|message| match message {
+            react_plug::PluginMessage::Message(message) => format!("{:?}", message),
+            message => panic!("Unexpected message: {:?}", message),
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(responses, ["Pong", r#"Title("Synth")"#, "Area(12)"]);
+
+    let bindings = react_plug::bindings::inventory::iter::<react_plug::bindings::MessagesBindings>
+        .into_iter()
+        .next()
+        .unwrap();
+    assert!(bindings.messages_ts.contains("ping: () =>"));
+    assert!(bindings.messages_ts.contains("setTitle: (payload:"));
+}
@@ -0,0 +1,27 @@
+use nih_plug::prelude::*;
+use react_plug::bindings::ParamsBindings;
+
+react_plug::define_params! {
+    ExampleParams {
+        wave_shape: EnumParam {
+            name: "Wave Shape",
+            default_value: Square,
+            variants: Waveform {
+                Sine: { name: "Sine Wave", id: "sine" },
+                Square: { name: "Square Wave", id: "square" },
+                Dirac: { id: "dirac" },
+            },
+        },
+    }
+}
+
+fn main() {
+    assert_eq!(Waveform::variants(), ["Sine Wave", "Square Wave", "Dirac"]);
+    assert_eq!(Waveform::ids(), Some(&["sine", "square", "dirac"][..]));
+
+    let ts = ExampleParams::default().ts_constructor(&|id| id.to_string());
+    assert!(ts.contains(r#"defaultValue: "square""#));
+    assert!(ts.contains(
+        r#"variants: { "sine": "Sine Wave", "square": "Square Wave", "dirac": "Dirac" }"#
+    ));
+}
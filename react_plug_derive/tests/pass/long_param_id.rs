@@ -0,0 +1,20 @@
+use nih_plug::prelude::*;
+use react_plug::prelude::*;
+
+const ID: &str = "a-parameter-id-that-is-a-lot-longer-than-thirty-two-characters";
+
+react_plug::define_params! {
+    ExampleParams {
+        gain: FloatParam {
+            name: "Gain",
+            id: "a-parameter-id-that-is-a-lot-longer-than-thirty-two-characters",
+            default_value: 0.5,
+            range: FloatRange::Linear { min: 0.0, max: 1.0 },
+        },
+    }
+}
+
+fn main() {
+    assert_eq!(ExampleParamsId::from_id(ID), Some(ExampleParamsId::Gain));
+    assert_eq!(ExampleParamsId::Gain.as_str(), ID);
+}
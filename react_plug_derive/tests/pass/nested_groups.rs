@@ -0,0 +1,47 @@
+use nih_plug::prelude::*;
+use react_plug::bindings::ParamsBindings;
+use react_plug::prelude::*;
+
+react_plug::define_params! {
+    #[nested]
+    BandParams {
+        frequency: FloatParam {
+            name: "Frequency",
+            default_value: 1000.0,
+            range: FloatRange::Linear { min: 20.0, max: 20000.0 },
+        },
+    }
+}
+
+react_plug::define_params! {
+    ExampleParams {
+        gain: FloatParam {
+            name: "Gain",
+            default_value: 0.5,
+            range: FloatRange::Linear { min: 0.0, max: 1.0 },
+        },
+        #[nested(id_prefix = "low", group = "Low")]
+        low: BandParams,
+        #[nested(array, group = "Band")]
+        bands: [BandParams; 2],
+    }
+}
+
+fn main() {
+    let params = ExampleParams::default();
+    let ts = params.ts_constructor(&|id| id.to_string());
+
+    assert!(ts.contains(r#"id: "low_frequency""#));
+    assert!(ts.contains(r#"id: "frequency_1""#));
+    assert!(ts.contains(r#"id: "frequency_2""#));
+
+    assert_eq!(
+        ExampleParamsId::Low(BandParamsId::Frequency).as_str(),
+        "low_frequency"
+    );
+    assert_eq!(
+        ExampleParamsId::from_id("frequency_2"),
+        Some(ExampleParamsId::Bands(1, BandParamsId::Frequency))
+    );
+    assert_eq!(ExampleParamsId::from_id("frequency_3"), None);
+}
@@ -0,0 +1,43 @@
+use nih_plug::prelude::*;
+use react_plug::bindings::ParamsBindings;
+use std::collections::BTreeMap;
+
+react_plug::define_params! {
+    ExampleParams {
+        gain: FloatParam {
+            name: "Gain",
+            default_value: 0.5,
+            range: FloatRange::Linear { min: 0.0, max: 1.0 },
+        },
+        sample_path: Persist<Option<String>> {
+            key: "sample-path",
+            default_value: None,
+        },
+        pattern: Persist<Vec<u8>> {
+            default_value: vec![1, 0, 0, 1],
+        },
+    }
+}
+
+fn main() {
+    let params = ExampleParams::default();
+    assert_eq!(*params.pattern.read().unwrap(), [1, 0, 0, 1]);
+
+    *params.sample_path.write().unwrap() = Some(String::from("kick.wav"));
+    let fields = params.serialize_fields();
+    assert_eq!(fields["sample-path"], r#""kick.wav""#);
+    assert_eq!(fields["pattern"], "[1,0,0,1]");
+
+    let mut state = BTreeMap::new();
+    state.insert(String::from("pattern"), String::from("[0,1]"));
+    params.deserialize_fields(&state);
+    assert_eq!(*params.pattern.read().unwrap(), [0, 1]);
+
+    let ts = params.ts_constructor(&|id| id.to_string());
+    assert!(ts.contains(
+        r#"sample_path: new ReactPlug.PersistentField({ key: "sample-path", defaultValue: null })"#
+    ));
+    assert!(ts.contains(
+        r#"pattern: new ReactPlug.PersistentField({ key: "pattern", defaultValue: [1,0,0,1] })"#
+    ));
+}
@@ -0,0 +1,46 @@
+use nih_plug::prelude::*;
+use react_plug::prelude::*;
+use react_plug::UnknownParamId;
+
+react_plug::define_params! {
+    ExampleParams {
+        gain: FloatParam {
+            name: "Gain",
+            default_value: 0.5,
+            range: FloatRange::Linear { min: 0.0, max: 1.0 },
+        },
+        muted: BoolParam {
+            name: "Muted",
+            id: "mute",
+            default_value: false,
+        },
+    }
+}
+
+fn change(id: &str) -> ParamChange {
+    ParamChange {
+        id: id.to_string(),
+        value: 1.0,
+        text: None,
+    }
+}
+
+fn main() {
+    let id: ExampleParamsId = "mute".parse().unwrap();
+    assert_eq!(id, ExampleParamsId::Muted);
+    assert_eq!(id.as_str(), "mute");
+    assert_eq!(id.to_string(), "mute");
+
+    let typed = change("gain").into_typed::<ExampleParamsId>().unwrap();
+    assert_eq!(typed.id, ExampleParamsId::Gain);
+    assert_eq!(typed.id.as_str(), "gain");
+    assert_eq!(typed.value, 1.0);
+
+    assert_eq!(
+        change("muted").into_typed::<ExampleParamsId>().unwrap_err(),
+        UnknownParamId(String::from("muted"))
+    );
+
+    let ts = react_plug::bindings::params_ts(&ExampleParams::default());
+    assert!(ts.contains(r#"export type ParamId = "gain" | "mute";"#));
+}
@@ -0,0 +1,12 @@
+react_plug::define_params! {
+    ExampleParams {
+        #[id = "gain"]
+        gain: FloatParam {
+            name: "Gain",
+            default_value: 1.0,
+            range: FloatRange::Linear { min: 0.0, max: 1.0 },
+        },
+    }
+}
+
+fn main() {}
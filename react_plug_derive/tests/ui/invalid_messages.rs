@@ -0,0 +1,12 @@
+react_plug::define_messages! {
+    #[serde(rename_all = "camelCase")]
+    enum GuiMessage {
+        Ping,
+    }
+
+    enum PluginMessage {
+        Pong,
+    }
+}
+
+fn main() {}
@@ -0,0 +1,16 @@
+react_plug::define_params! {
+    ExampleParams {
+        int_test: IntParam {
+            name: "Int Test",
+            default_value: 0,
+            range: IntRange::Skewed { min: 0, max: 10 },
+        },
+        float_test: FloatParam {
+            name: "Float Test",
+            default_value: 0.0,
+            range: RANGE,
+        },
+    }
+}
+
+fn main() {}
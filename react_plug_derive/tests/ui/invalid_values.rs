@@ -0,0 +1,30 @@
+react_plug::define_params! {
+    ExampleParams {
+        gain: FloatParam {
+            name: "Gain",
+            default_value: 2.0,
+            range: FloatRange::Linear { min: -1.0, max: 1.0 },
+        },
+        mix: FloatParam {
+            name: "Mix",
+            default_value: 0.5,
+            range: FloatRange::Skewed { min: 1.0, max: 0.0, factor: 0.5 },
+        },
+        voices: IntParam {
+            name: "Voices",
+            id: "gain",
+            default_value: 1,
+            range: IntRange::Linear { min: 1, max: 8 },
+        },
+        wave_shape: EnumParam {
+            name: "Wave Shape",
+            default_value: Saw,
+            variants: Waveform {
+                Sine: "Sine Wave",
+                Square: "Square Wave",
+            },
+        },
+    }
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+react_plug::define_params! {
+    ExampleParams {
+        gain: FloatParam {
+            name: "Gain",
+            default_value: 1.0,
+        },
+    }
+}
+
+fn main() {}
@@ -0,0 +1,14 @@
+react_plug::define_params! {
+    ExampleParams {
+        wave_shape: EnumParam {
+            name: "Wave Shape",
+            default_value: Sine,
+            variants: Waveform {
+                Sine: { name: "Sine Wave", id: "sine" },
+                Square: "Square Wave",
+            },
+        },
+    }
+}
+
+fn main() {}
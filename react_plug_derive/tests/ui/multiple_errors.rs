@@ -0,0 +1,15 @@
+react_plug::define_params! {
+    ExampleParams {
+        gain: FloatParam {
+            name: "Gain",
+            range: FloatRange::Linear { min: 0.0, max: 1.0 },
+        },
+        muted: BoolParam {
+            name: "Muted",
+            default_value: false,
+            unit: " dB",
+        },
+    }
+}
+
+fn main() {}
@@ -1,13 +1,17 @@
-import {createContext, FC, ReactNode, useContext, useEffect, useRef} from 'react';
+import {createContext, FC, ReactNode, useContext, useEffect, useRef, useState} from 'react';
 import {EventEmitter} from 'events';
 
-import {type Params, createParameters} from './Params';
+import {type Params, type ParamChange, createParameters} from "./Params.ts";
 import {type GuiMessage} from "./GuiMessage.ts";
 import {type PluginMessage} from "./PluginMessage.ts";
 import * as ReactPlug from "./react-plug.ts";
 
 interface ContextType {
   parameters: Params;
+  /** Describes all of the plugin's parameters, including those without bindings. */
+  metadata: ReactPlug.ParamInfo[];
+  /** The normalized values of all of the plugin's parameters, by ID. */
+  values: { [id: string]: number };
   sendToPlugin: (message: GuiMessage) => void;
   addMessageListener: (action: (message: PluginMessage) => void) => void;
   removeMessageListener: (action: (message: PluginMessage) => void) => void;
@@ -15,22 +19,64 @@ interface ContextType {
 
 const PluginContext = createContext<ContextType | undefined>(undefined);
 
-const PluginProvider: FC<{ children: ReactNode }> = ({children}) => {
+/** Emits the internal React-Plug messages that are consumed by hooks like `usePresets`. */
+const internalEvents = new EventEmitter().setMaxListeners(0);
+
+let historyState: ReactPlug.HistoryState = {can_undo: false, can_redo: false};
+
+const displayValues: { [id: string]: number } = {};
+
+const PluginProvider: FC<{
+  children: ReactNode,
+  /** Forward console output and uncaught errors to the plugin's logger. */
+  forwardConsole?: boolean,
+}> = ({children, forwardConsole}) => {
   const eventEmitter = useRef(new EventEmitter());
 
   const addMessageListener = (action: (message: PluginMessage) => void) => eventEmitter.current.on('pluginMessage', action as (...args: any[]) => void);
   const removeMessageListener = (action: (message: PluginMessage) => void) => eventEmitter.current.off('pluginMessage', action as (...args: any[]) => void);
   const parameters = createParameters();
+  const allParameters = ReactPlug.flattenParameters(parameters);
+  const persistentFields = ReactPlug.flattenPersistentFields(parameters);
+  const [metadata, setMetadata] = useState<ReactPlug.ParamInfo[]>([]);
+  const [values, setValues] = useState<{ [id: string]: number }>({});
 
   useEffect(() => {
+    if (forwardConsole) ReactPlug.forwardConsole();
+
     ReactPlug.sendToPlugin('Init');
 
     (window as unknown as ReactPlug.Window).onPluginMessage = (message: ReactPlug.PluginMessage<unknown>) => {
-      if ("ParamChange" in message) {
-        const paramChange = (message.ParamChange as ReactPlug.ParamChange)
-        console.log("Parameter change (Plugin -> GUI)", paramChange);
+      if ("ParamInfo" in message) {
+        setMetadata(message.ParamInfo);
+      } else if ("ParamChange" in message) {
+        const paramChange = (message.ParamChange as ParamChange)
+        console.debug("Parameter change (Plugin -> GUI)", paramChange);
+
+        setValues(values => ({...values, [paramChange.id]: paramChange.value}));
 
-        Object.values(parameters).find(param => param.id == paramChange.id)?._setNormalizedValue(paramChange.value);
+        allParameters.find(param => param.id == paramChange.id)?._setNormalizedValue(paramChange.value, paramChange.text);
+      } else if ("ParamDisplayValue" in message) {
+        const {id, value} = message.ParamDisplayValue;
+        displayValues[id] = value;
+        internalEvents.emit('displayValue', message.ParamDisplayValue);
+      } else if ("ParseError" in message) {
+        const parseError = message.ParseError;
+        allParameters.find(param => param.id == parseError.id)?._setParseError(parseError.message);
+      } else if ("PersistedField" in message) {
+        const {key, value} = message.PersistedField;
+        persistentFields.find(field => field.key == key)?._setValue(value);
+      } else if ("FormattedValue" in message) {
+        ReactPlug._resolveFormattedValue(message.FormattedValue);
+      } else if ("FormatError" in message) {
+        ReactPlug._rejectFormattedValue(message.FormatError);
+      } else if ("History" in message) {
+        historyState = message.History;
+        internalEvents.emit('history', message.History);
+      } else if ("MidiLearn" in message) {
+        internalEvents.emit('midiLearn', message.MidiLearn);
+      } else if ("Preset" in message) {
+        internalEvents.emit('preset', message.Preset);
       } else if ("Message" in message) {
         eventEmitter.current.emit('pluginMessage', message.Message)
       }
@@ -40,8 +86,10 @@ const PluginProvider: FC<{ children: ReactNode }> = ({children}) => {
   return (
     <PluginContext.Provider value={{
       parameters,
+      metadata,
+      values,
       sendToPlugin: (message: GuiMessage) => {
-        console.log("Message", message)
+        console.debug("Message", message)
         ReactPlug.sendToPlugin({"Message": message})
       },
       addMessageListener,
@@ -60,4 +108,142 @@ export const usePluginContext = () => {
   return context;
 };
 
+/**
+ * Lists, loads, saves, renames and deletes presets. This requires the editor to be
+ * created using `with_presets()`.
+ */
+export const usePresets = () => {
+  const [presets, setPresets] = useState<ReactPlug.PresetInfo[]>([]);
+  const [current, setCurrent] = useState<ReactPlug.PresetInfo | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    const listener = (event: ReactPlug.PresetEvent) => {
+      if ("List" in event) {
+        setPresets(event.List);
+      } else if ("Loaded" in event) {
+        setCurrent(event.Loaded);
+        setError(null);
+      } else if ("Error" in event) {
+        setError(event.Error);
+      }
+    };
+
+    internalEvents.on('preset', listener);
+    ReactPlug.sendToPlugin({Preset: "List"});
+
+    return () => {
+      internalEvents.off('preset', listener);
+    };
+  }, []);
+
+  return {
+    presets,
+    current,
+    error,
+    refresh: () => ReactPlug.sendToPlugin({Preset: "List"}),
+    load: (preset: ReactPlug.PresetInfo) => ReactPlug.sendToPlugin({Preset: {Load: preset}}),
+    save: (name: string) => ReactPlug.sendToPlugin({Preset: {Save: name}}),
+    rename: (from: string, to: string) => ReactPlug.sendToPlugin({Preset: {Rename: {from, to}}}),
+    remove: (name: string) => ReactPlug.sendToPlugin({Preset: {Delete: name}}),
+  };
+};
+
+/**
+ * The parameter marked as the plugin's bypass parameter using
+ * `flags: ParamFlags::BYPASS`, if any.
+ */
+export const useBypass = () => {
+  const {parameters} = usePluginContext();
+  return ReactPlug.findBypassParameter(parameters);
+};
+
+/**
+ * Undo and redo parameter changes made from the GUI. Use `beginGesture()` and
+ * `endGesture()` on parameters to group changes into a single undo step.
+ */
+export const useHistory = () => {
+  const [state, setState] = useState(historyState);
+
+  useEffect(() => {
+    const listener = (state: ReactPlug.HistoryState) => setState(state);
+    internalEvents.on('history', listener);
+
+    return () => {
+      internalEvents.off('history', listener);
+    };
+  }, []);
+
+  return {
+    canUndo: state.can_undo,
+    canRedo: state.can_redo,
+    undo: () => ReactPlug.sendToPlugin("Undo"),
+    redo: () => ReactPlug.sendToPlugin("Redo"),
+  };
+};
+
+/**
+ * The value of a parameter as it's currently seen by the audio thread, e.g. while
+ * it's being smoothed. This requires the editor to be created using
+ * `with_display_value()` or `with_modulated_display_values()` for this parameter.
+ */
+export const useDisplayValue = (id: string) => {
+  const [value, setValue] = useState<number | undefined>(displayValues[id]);
+
+  useEffect(() => {
+    const listener = (displayValue: ReactPlug.ParamDisplayValue) => {
+      if (displayValue.id == id) setValue(displayValue.value);
+    };
+
+    setValue(displayValues[id]);
+    internalEvents.on('displayValue', listener);
+
+    return () => {
+      internalEvents.off('displayValue', listener);
+    };
+  }, [id]);
+
+  return value;
+};
+
+/**
+ * Map MIDI CCs to parameters. `learn()` waits for the next incoming CC and maps it
+ * to the given parameter. This requires the editor to be created using
+ * `with_midi_learn()`.
+ */
+export const useMidiLearn = () => {
+  const [mappings, setMappings] = useState<ReactPlug.MidiMappings>({});
+  const [learning, setLearning] = useState<string | null>(null);
+
+  useEffect(() => {
+    const listener = (event: ReactPlug.MidiLearnEvent) => {
+      if ("Learning" in event) {
+        setLearning(event.Learning);
+      } else if ("Mappings" in event) {
+        setMappings(event.Mappings);
+      }
+    };
+
+    internalEvents.on('midiLearn', listener);
+    ReactPlug.sendToPlugin({MidiLearn: "List"});
+
+    return () => {
+      internalEvents.off('midiLearn', listener);
+    };
+  }, []);
+
+  return {
+    mappings,
+    learning,
+    /** The CC that is mapped to the parameter with the given ID, if any. */
+    ccFor: (id: string) => {
+      const cc = Object.keys(mappings).find(cc => mappings[cc] == id);
+      return cc === undefined ? undefined : Number(cc);
+    },
+    learn: (id: string) => ReactPlug.sendToPlugin({MidiLearn: {Learn: id}}),
+    cancel: () => ReactPlug.sendToPlugin({MidiLearn: "Cancel"}),
+    forget: (id: string) => ReactPlug.sendToPlugin({MidiLearn: {Forget: id}}),
+  };
+};
+
 export default PluginProvider;
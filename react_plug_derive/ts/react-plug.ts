@@ -2,15 +2,103 @@
 /* eslint-disable react-hooks/rules-of-hooks */
 import {useState} from "react";
 
-export type ParamChange = { id: string, value: number }
+export type ParamChange<Id extends string = string> = { id: Id, value: number, text?: string }
+
+export type ParamKind = "FloatParam" | "IntParam" | "BoolParam" | "EnumParam";
+
+/** Describes a parameter at runtime, independently of the generated bindings. */
+export type ParamInfo = {
+  id: string,
+  kind: ParamKind,
+  name: string,
+  /** The parameter's description, from its doc comment or `description` field. */
+  description: string | null,
+  unit: string,
+  /** The path of the group this parameter is nested in. Empty for top-level parameters. */
+  group: string,
+  stepCount: number | null,
+  defaultNormalizedValue: number,
+  flags: Required<ParamFlags>,
+  /** The names of all variants, for enum parameters. */
+  variants: string[] | null,
+  polyModulationId: number | null,
+}
+
+export type FormattedValue = { id: string, normalized: number, text: string }
+
+export type FormatError = { id: string, normalized: number, message: string }
+
+/** A parameter's value as it's currently seen by the audio thread, e.g. while smoothing. */
+export type ParamDisplayValue = { id: string, value: number }
+
+/** The value of a persisted field, in the same JSON representation as in the plugin's state. */
+export type PersistedField = { key: string, value: unknown }
+
+export type ParseError = { id: string, text: string, message: string }
+
+export type HistoryState = { can_undo: boolean, can_redo: boolean }
+
+export type MidiMappings = { [cc: string]: string }
+
+export type MidiLearnRequest =
+  "List" |
+  { "Learn": string } |
+  "Cancel" |
+  { "Forget": string };
+
+export type MidiLearnEvent =
+  { "Learning": string | null } |
+  { "Learned": { cc: number, id: string } } |
+  { "Mappings": MidiMappings };
+
+export type LogLevel = "Debug" | "Info" | "Warn" | "Error";
+
+export type NoteEvent =
+  { "NoteOn": { channel: number, note: number, velocity: number } } |
+  { "NoteOff": { channel: number, note: number, velocity: number } } |
+  { "PitchBend": { channel: number, value: number } };
+
+export type PresetInfo = { name: string, factory: boolean }
+
+export type PresetRequest =
+  "List" |
+  { "Load": PresetInfo } |
+  { "Save": string } |
+  { "Rename": { from: string, to: string } } |
+  { "Delete": string };
+
+export type PresetEvent =
+  { "List": PresetInfo[] } |
+  { "Loaded": PresetInfo } |
+  { "Error": string };
 
 export type PluginMessage<M> =
+  { "ParamInfo": ParamInfo[] } |
   { "ParamChange": ParamChange } |
+  { "ParamDisplayValue": ParamDisplayValue } |
+  { "FormattedValue": FormattedValue } |
+  { "FormatError": FormatError } |
+  { "ParseError": ParseError } |
+  { "PersistedField": PersistedField } |
+  { "History": HistoryState } |
+  { "MidiLearn": MidiLearnEvent } |
+  { "Preset": PresetEvent } |
   { "Message": M };
 
 export type GUIMessage<M> =
   { "ParamChange": ParamChange } |
+  { "SetParamFromString": { id: string, text: string } } |
+  { "BeginGesture": string } |
+  { "EndGesture": string } |
+  { "FormatValue": { id: string, normalized: number } } |
   "Init" |
+  { "PersistedField": PersistedField } |
+  "Undo" |
+  "Redo" |
+  { "Log": { level: LogLevel, message: string } } |
+  { "MidiLearn": MidiLearnRequest } |
+  { "Note": NoteEvent } |
+  { "Preset": PresetRequest } |
   { "Message": M };
 
 export type ValueToString<T> = (value: T) => string;
@@ -31,11 +119,213 @@ export function sendToPlugin<M>(message: GUIMessage<M>) {
   (window as unknown as Window).ipc.postMessage(JSON.stringify(message));
 }
 
+/** The payload of a message variant, e.g. `string` for `{ "Foo": string }`. */
+export type MessagePayload<M, V extends string> = M extends { [key in V]: infer P } ? P : never;
+
+/** Anything plugin messages can be listened to on, like the PluginContext. */
+export type MessageListeners<M> = {
+  addMessageListener: (action: (message: M) => void) => void;
+  removeMessageListener: (action: (message: M) => void) => void;
+}
+
+/**
+ * Calls `listener` with the payload of every plugin message of the given variant.
+ * Returns a function that removes the listener, so that it can be returned from
+ * `useEffect()`.
+ */
+export function onMessage<M, V extends string>(
+  listeners: MessageListeners<M>,
+  variant: V,
+  listener: (payload: MessagePayload<M, V>) => void
+): () => void {
+  const action = (message: M) => {
+    if ((message as unknown) === variant) {
+      listener(undefined as never);
+    } else if (typeof message === "object" && message !== null && variant in message) {
+      listener((message as { [key in V]: MessagePayload<M, V> })[variant]);
+    }
+  };
+
+  listeners.addMessageListener(action);
+  return () => listeners.removeMessageListener(action);
+}
+
+/**
+ * Send a note event to the plugin's audio thread, e.g. from an on-screen keyboard.
+ * Velocities and pitch bend values are normalized to [0, 1]. This requires the
+ * editor to be created using `with_note_sender()`.
+ */
+export function sendNote(event: NoteEvent) {
+  sendToPlugin({Note: event});
+}
+
+type PendingFormat = { resolve: (text: string) => void, reject: (error: Error) => void };
+
+const pendingFormats = new Map<string, PendingFormat[]>();
+
+/**
+ * Formats a normalized value of a parameter using the plugin's own formatter. The
+ * promise is rejected if the plugin couldn't format the value, e.g. for unknown IDs.
+ */
+export function formatValue(id: string, normalized: number): Promise<string> {
+  return new Promise((resolve, reject) => {
+    pendingFormats.set(id, [...(pendingFormats.get(id) ?? []), {resolve, reject}]);
+    sendToPlugin({FormatValue: {id, normalized}});
+  });
+}
+
+/**
+ * Resolves the oldest pending `formatValue()` call for a parameter. This is used
+ * internally by the React-Plug framework and should not be called directly.
+ */
+export function _resolveFormattedValue({id, text}: FormattedValue) {
+  pendingFormats.get(id)?.shift()?.resolve(text);
+}
+
+/**
+ * Rejects the oldest pending `formatValue()` call for a parameter. This is used
+ * internally by the React-Plug framework and should not be called directly.
+ */
+export function _rejectFormattedValue({id, message}: FormatError) {
+  pendingFormats.get(id)?.shift()?.reject(new Error(message));
+}
+
+let forwardingLog = false;
+
+/** Sends a log message to the plugin, which writes it to nih-plug's logger. */
+export function logToPlugin(level: LogLevel, ...args: unknown[]) {
+  // Sending a message logs it using console.debug(), which would be forwarded again
+  if (forwardingLog) return;
+
+  forwardingLog = true;
+  try {
+    const message = args.map(arg => {
+      if (typeof arg === "string") return arg;
+      if (arg instanceof Error) return arg.stack || arg.message;
+      try {
+        return JSON.stringify(arg);
+      } catch {
+        return String(arg);
+      }
+    }).join(" ");
+
+    sendToPlugin({Log: {level, message}});
+  } finally {
+    forwardingLog = false;
+  }
+}
+
+let consoleForwarded = false;
+
+/**
+ * Forwards `console.debug/log/info/warn/error` calls, uncaught errors and unhandled
+ * promise rejections to the plugin. This is useful inside of a DAW, where the
+ * WebView's developer tools usually can't be opened.
+ */
+export function forwardConsole() {
+  if (consoleForwarded) return;
+  consoleForwarded = true;
+
+  const levels: [keyof Console & ("debug" | "log" | "info" | "warn" | "error"), LogLevel][] = [
+    ["debug", "Debug"],
+    ["log", "Info"],
+    ["info", "Info"],
+    ["warn", "Warn"],
+    ["error", "Error"],
+  ];
+
+  levels.forEach(([method, level]) => {
+    const original = console[method].bind(console);
+    console[method] = (...args: unknown[]) => {
+      original(...args);
+      logToPlugin(level, ...args);
+    };
+  });
+
+  window.addEventListener("error", (event) => {
+    logToPlugin("Error", `Uncaught ${event.error ?? event.message} (${event.filename}:${event.lineno}:${event.colno})`);
+  });
+
+  window.addEventListener("unhandledrejection", (event) => {
+    logToPlugin("Error", "Unhandled promise rejection:", event.reason);
+  });
+}
+
+export type AnyParameter =
+  parameters.FloatParam |
+  parameters.IntParam |
+  parameters.BoolParam |
+  parameters.EnumParam;
+
+/** Collects all parameters of a parameters object, including those of nested groups. */
+export function flattenParameters(params: object): AnyParameter[] {
+  return collect(params, (value): value is AnyParameter =>
+    value instanceof parameters.FloatParam ||
+    value instanceof parameters.IntParam ||
+    value instanceof parameters.BoolParam ||
+    value instanceof parameters.EnumParam);
+}
+
+/** Collects all persisted fields of a parameters object, including those of nested groups. */
+export function flattenPersistentFields(params: object): PersistentField<unknown>[] {
+  return collect(params, (value): value is PersistentField<unknown> => value instanceof PersistentField);
+}
+
+/** Finds the parameter marked as the plugin's bypass parameter, if any. */
+export function findBypassParameter(params: object): parameters.BoolParam | undefined {
+  return collect(params, (value): value is parameters.BoolParam =>
+    value instanceof parameters.BoolParam && !!value.flags.bypass)[0];
+}
+
+function collect<T>(params: object, matches: (value: unknown) => value is T): T[] {
+  return Object.values(params).flatMap(value => {
+    if (matches(value)) return [value];
+
+    return typeof value == "object" && value !== null ? collect(value, matches) : [];
+  });
+}
+
+/**
+ * A persisted field of the plugin's parameters that isn't a parameter, e.g. a
+ * sample path or a sequencer pattern. Its value is stored in the plugin's state,
+ * and changes made from the GUI are sent to the plugin.
+ */
+export class PersistentField<T> {
+  /** The key this field is persisted with. */
+  key: string;
+  value: T;
+  defaultValue: T;
+
+  /** Sets the value and sends it to the plugin. */
+  setValue: (value: T) => void;
+  /**
+   * Updates the value when it was changed by the plugin. This is used internally by
+   * the React-Plug framework and should not be called directly.
+   */
+  _setValue: (value: T) => void;
+
+  constructor({key, defaultValue}: { key: string, defaultValue: T }) {
+    this.key = key;
+    this.defaultValue = defaultValue;
+
+    const [value, setValue] = useState<T>(defaultValue);
+    this.value = value;
+
+    this._setValue = setValue;
+    this.setValue = (value: T) => {
+      setValue(value);
+      sendToPlugin({PersistedField: {key, value}});
+    };
+  }
+}
+
 export interface Parameter<T> {
   /** The unique identifier for this parameter. */
   id: string;
   /** The human-readable name for this parameter. */
   name: string;
+  /** A description of this parameter, e.g. for tooltips. */
+  description?: string;
   /** The unit label for this parameter, if any. */
   unit?: string;
   /**
@@ -52,6 +342,19 @@ export interface Parameter<T> {
   setValue: (value: T) => void;
   /** Reset the current value for this parameter to its default value. */
   resetValue: () => void;
+  /**
+   * Set the current value for this parameter from text entered by the user, e.g.
+   * "-6 dB". The text is parsed by the plugin, using the parameter's own
+   * `string_to_value` function. If it can't be parsed, `parseError` is set.
+   */
+  setValueFromString: (text: string) => void;
+  /** Why the text passed to `setValueFromString()` couldn't be parsed, if it couldn't. */
+  parseError?: string;
+  /**
+   * Set the parse error for this parameter. This is used internally by the
+   * React-Plug framework and should not be called directly.
+   */
+  _setParseError: (message?: string) => void;
   /** The unnormalized default value for this parameter. */
   defaultPlainValue: T;
 
@@ -65,10 +368,34 @@ export interface Parameter<T> {
    * Set the current normalized value for this parameter. This is used internally
    * by the React-Plug framework and should not be called directly.
    */
-  _setNormalizedValue: (value: number) => void;
+  _setNormalizedValue: (value: number, text?: string) => void;
   /** Get the normalized [0, 1] default value for this parameter. */
   defaultNormalizedValue: number;
 
+  // = GESTURES ================================================================= //
+
+  /**
+   * Begin a gesture for this parameter, e.g. when the user starts dragging a knob.
+   * All value changes until `endGesture()` is called are grouped into one undo step.
+   */
+  beginGesture: () => void;
+  /** End the gesture started with `beginGesture()`. */
+  endGesture: () => void;
+
+  // = FORMATTING =============================================================== //
+
+  /**
+   * The current value as text. If the editor was created using
+   * `with_value_text(true)`, this is formatted by the plugin itself, so it is
+   * always the same as what the host displays. Otherwise, `value_to_string` is used.
+   */
+  displayText: string;
+  /**
+   * Format any normalized value using the plugin's own formatter, e.g. to preview
+   * a value while dragging.
+   */
+  formatValue: (normalized: number) => Promise<string>;
+
   // = STEPPING ================================================================= //
 
   /** The number of steps for this parameter, if it is discrete. Used for the host’s generic UI. */
@@ -136,6 +463,11 @@ export type ParamFlags = {
    * plugin's own GUI or from the host's generic UI.
    */
   nonAutomatable?: boolean,
+  /**
+   * Hides the parameter in the host's generic UI for this plugin. This also
+   * implies `nonAutomatable`.
+   */
+  hidden?: boolean,
   /**
    * Don’t show this parameter when generating a generic UI for the plugin using
    * one of NIH-plug’s generic UI widgets.
@@ -149,6 +481,8 @@ export type ParamFlags = {
 export type ParamOptions<T> = {
   id: string,
   name: string,
+  /** A description of this parameter, e.g. for tooltips. */
+  description?: string,
   defaultValue: T,
   value_to_string?: ValueToString<T>
   /** The unit label for this parameter. */
@@ -454,6 +788,86 @@ export module ranges {
     }
   }
 
+  /**
+   * A range that is described by values sampled at evenly spaced normalized values,
+   * which are interpolated linearly. This is used for parameters whose range isn't
+   * known in the GUI, e.g. when they are declared using `#[derive(ReactPlugParams)]`.
+   */
+  export class SampledFloatRange implements FloatRange {
+    values: number[];
+    min: number;
+    max: number;
+
+    clamp = (n: number) => clamp(n, Math.min(this.min, this.max), Math.max(this.min, this.max));
+    normalize = (n: number) => {
+      const value = this.clamp(n);
+      const ascending = this.max >= this.min;
+
+      let low = 0;
+      let high = this.values.length - 1;
+      while (high - low > 1) {
+        const mid = Math.floor((low + high) / 2);
+        if ((this.values[mid] <= value) === ascending) {
+          low = mid;
+        } else {
+          high = mid;
+        }
+      }
+
+      const from = this.values[low];
+      const to = this.values[high];
+      const position = to === from ? 0 : (value - from) / (to - from);
+
+      return (low + position) / (this.values.length - 1);
+    };
+    unnormalize = (n: number) => {
+      const position = clamp(n, 0, 1) * (this.values.length - 1);
+      const low = Math.min(Math.floor(position), this.values.length - 2);
+
+      return this.values[low] + (position - low) * (this.values[low + 1] - this.values[low]);
+    };
+
+    previousStep = (from: number, stepSize?: number, finer?: boolean) => {
+      const normalized_naive_step_size = finer ? 0.005 : 0.02;
+      const naive_step = this.unnormalize(this.normalize(from) - normalized_naive_step_size);
+
+      let result;
+      if (stepSize === undefined) {
+        result = naive_step
+      } else if (Math.abs(naive_step - from) > stepSize) {
+        result = this.snapToStep(naive_step, stepSize)
+      } else {
+        result = from - stepSize
+      }
+
+      return this.clamp(result);
+    };
+
+    nextStep = (from: number, stepSize?: number, finer?: boolean) => {
+      const normalized_naive_step_size = finer ? 0.005 : 0.02;
+      const naive_step = this.unnormalize(this.normalize(from) + normalized_naive_step_size);
+
+      let result;
+      if (stepSize === undefined) {
+        result = naive_step
+      } else if (Math.abs(naive_step - from) > stepSize) {
+        result = this.snapToStep(naive_step, stepSize)
+      } else {
+        result = from + stepSize
+      }
+
+      return this.clamp(result);
+    };
+
+    snapToStep = (value: number, stepSize: number) => this.clamp(Math.round(value / stepSize) * stepSize);
+
+    constructor({values}: { values: number[] }) {
+      this.values = values;
+      this.min = values[0];
+      this.max = values[values.length - 1];
+    }
+  }
+
   export interface IntRange {
     min: number;
     max: number;
@@ -521,6 +935,7 @@ export module parameters {
   export class FloatParam implements Parameter<number> {
     id: string;
     name: string;
+    description?: string;
     unit?: string;
     polyModulationId?: number;
     value: number;
@@ -539,13 +954,21 @@ export module parameters {
     setValue: (value: number) => void;
     resetValue: () => void;
     setNormalizedValue: (value: number) => void;
-    _setNormalizedValue: (value: number) => void;
+    _setNormalizedValue: (value: number, text?: string) => void;
+    beginGesture: () => void;
+    endGesture: () => void;
+    displayText: string;
+    formatValue: (normalized: number) => Promise<string>;
+    setValueFromString: (text: string) => void;
+    parseError?: string;
+    _setParseError: (message?: string) => void;
 
     value_to_string: ValueToString<number>;
 
     constructor({
                   id,
                   name,
+                  description,
                   defaultValue,
                   range,
                   value_to_string,
@@ -559,6 +982,7 @@ export module parameters {
     }) {
       this.id = id;
       this.name = name;
+      this.description = description;
       this.unit = unit;
       this.polyModulationId = polyModulationId;
       this.flags = flags || {};
@@ -578,7 +1002,15 @@ export module parameters {
       this.value = value;
       this.normalizedValue = normalizedValue;
 
-      this._setNormalizedValue = (value) => {
+      const [valueText, setValueText] = useState<string | undefined>(undefined);
+      this.displayText = valueText ?? this.value_to_string(value);
+
+      const [parseError, setParseError] = useState<string | undefined>(undefined);
+      this.parseError = parseError;
+      this._setParseError = setParseError;
+
+      this._setNormalizedValue = (value, text) => {
+        setValueText(text);
         if (this.normalizedValue == value) return;
 
         setValue(this.previewPlain(value));
@@ -586,12 +1018,14 @@ export module parameters {
       };
 
       this.setValue = (value: number) => {
+        setValueText(undefined);
         setValue(value);
         setNormalizedValue(this.previewNormalized(value));
         sendToPlugin({ParamChange: {id, value: this.previewNormalized(value)}});
       }
 
       this.setNormalizedValue = (value: number) => {
+        setValueText(undefined);
         setValue(this.previewPlain(value));
         setNormalizedValue(value);
         sendToPlugin({ParamChange: {id, value}});
@@ -601,6 +1035,14 @@ export module parameters {
         this.setNormalizedValue(defaultNormalizedValue);
       }
 
+      this.beginGesture = () => sendToPlugin({BeginGesture: id});
+      this.endGesture = () => sendToPlugin({EndGesture: id});
+      this.formatValue = (normalized) => formatValue(id, normalized);
+      this.setValueFromString = (text) => {
+        setParseError(undefined);
+        sendToPlugin({SetParamFromString: {id, text}});
+      };
+
       this.previousStep = (from, finer) => range.previousStep(from, stepSize, finer);
       this.nextStep = (from, finer) => range.nextStep(from, stepSize, finer);
 
@@ -612,6 +1054,7 @@ export module parameters {
   export class IntParam implements Parameter<number> {
     id: string;
     name: string;
+    description?: string;
     unit?: string;
     polyModulationId?: number;
     value: number;
@@ -630,13 +1073,21 @@ export module parameters {
     setValue: (value: number) => void;
     resetValue: () => void;
     setNormalizedValue: (value: number) => void;
-    _setNormalizedValue: (value: number) => void;
+    _setNormalizedValue: (value: number, text?: string) => void;
+    beginGesture: () => void;
+    endGesture: () => void;
+    displayText: string;
+    formatValue: (normalized: number) => Promise<string>;
+    setValueFromString: (text: string) => void;
+    parseError?: string;
+    _setParseError: (message?: string) => void;
 
     value_to_string: ValueToString<number>;
 
     constructor({
                   id,
                   name,
+                  description,
                   defaultValue,
                   range,
                   value_to_string,
@@ -648,6 +1099,7 @@ export module parameters {
     }) {
       this.id = id;
       this.name = name;
+      this.description = description;
       this.unit = unit;
       this.polyModulationId = polyModulationId;
       this.flags = flags || {};
@@ -667,18 +1119,28 @@ export module parameters {
       this.value = value;
       this.normalizedValue = normalizedValue;
 
-      this._setNormalizedValue = (value) => {
+      const [valueText, setValueText] = useState<string | undefined>(undefined);
+      this.displayText = valueText ?? this.value_to_string(value);
+
+      const [parseError, setParseError] = useState<string | undefined>(undefined);
+      this.parseError = parseError;
+      this._setParseError = setParseError;
+
+      this._setNormalizedValue = (value, text) => {
+        setValueText(text);
         setValue(this.previewPlain(value));
         setNormalizedValue(value);
       };
 
       this.setValue = (value: number) => {
+        setValueText(undefined);
         setValue(value);
         setNormalizedValue(this.previewNormalized(value));
         sendToPlugin({ParamChange: {id, value: this.previewNormalized(value)}});
       }
 
       this.setNormalizedValue = (value: number) => {
+        setValueText(undefined);
         setValue(this.previewPlain(value));
         setNormalizedValue(value);
         sendToPlugin({ParamChange: {id, value}});
@@ -688,6 +1150,14 @@ export module parameters {
         this.setNormalizedValue(defaultNormalizedValue);
       }
 
+      this.beginGesture = () => sendToPlugin({BeginGesture: id});
+      this.endGesture = () => sendToPlugin({EndGesture: id});
+      this.formatValue = (normalized) => formatValue(id, normalized);
+      this.setValueFromString = (text) => {
+        setParseError(undefined);
+        sendToPlugin({SetParamFromString: {id, text}});
+      };
+
       this.previousStep = range.previousStep;
       this.nextStep = range.nextStep;
       this.stepCount = range.stepCount;
@@ -700,6 +1170,7 @@ export module parameters {
   export class BoolParam implements Parameter<boolean> {
     id: string;
     name: string;
+    description?: string;
     polyModulationId?: number;
     value: boolean;
     unit?: "";
@@ -718,13 +1189,21 @@ export module parameters {
     setValue: (value: boolean) => void;
     resetValue: () => void;
     setNormalizedValue: (value: number) => void;
-    _setNormalizedValue: (value: number) => void;
+    _setNormalizedValue: (value: number, text?: string) => void;
+    beginGesture: () => void;
+    endGesture: () => void;
+    displayText: string;
+    formatValue: (normalized: number) => Promise<string>;
+    setValueFromString: (text: string) => void;
+    parseError?: string;
+    _setParseError: (message?: string) => void;
 
     value_to_string: ValueToString<boolean>;
 
-    constructor({id, name, defaultValue, polyModulationId, flags, value_to_string}: ParamOptions<boolean>) {
+    constructor({id, name, description, defaultValue, polyModulationId, flags, value_to_string}: ParamOptions<boolean>) {
       this.id = id;
       this.name = name;
+      this.description = description;
       this.polyModulationId = polyModulationId;
       this.flags = flags || {};
       this.value_to_string = value_to_string || ((n) => n ? "On" : "Off");
@@ -740,18 +1219,28 @@ export module parameters {
       this.value = value;
       this.normalizedValue = normalizedValue;
 
-      this._setNormalizedValue = (value) => {
+      const [valueText, setValueText] = useState<string | undefined>(undefined);
+      this.displayText = valueText ?? this.value_to_string(value);
+
+      const [parseError, setParseError] = useState<string | undefined>(undefined);
+      this.parseError = parseError;
+      this._setParseError = setParseError;
+
+      this._setNormalizedValue = (value, text) => {
+        setValueText(text);
         setValue(this.previewPlain(value));
         setNormalizedValue(value);
       };
 
       this.setValue = (value: boolean) => {
+        setValueText(undefined);
         sendToPlugin({ParamChange: {id, value: this.previewNormalized(value)}});
         setValue(value);
         setNormalizedValue(this.previewNormalized(value));
       }
 
       this.setNormalizedValue = (value: number) => {
+        setValueText(undefined);
         sendToPlugin({ParamChange: {id, value}});
         setValue(this.previewPlain(value));
         setNormalizedValue(value);
@@ -760,12 +1249,21 @@ export module parameters {
       this.resetValue = () => {
         this.setNormalizedValue(defaultNormalizedValue);
       }
+
+      this.beginGesture = () => sendToPlugin({BeginGesture: id});
+      this.endGesture = () => sendToPlugin({EndGesture: id});
+      this.formatValue = (normalized) => formatValue(id, normalized);
+      this.setValueFromString = (text) => {
+        setParseError(undefined);
+        sendToPlugin({SetParamFromString: {id, text}});
+      };
     }
   }
 
   export class EnumParam implements Parameter<string> {
     id: string;
     name: string;
+    description?: string;
     polyModulationId?: number;
     value: string;
     variants: { [key: string]: string };
@@ -785,15 +1283,23 @@ export module parameters {
     setValue: (value: string) => void;
     resetValue: () => void;
     setNormalizedValue: (value: number) => void;
-    _setNormalizedValue: (value: number) => void;
+    _setNormalizedValue: (value: number, text?: string) => void;
+    beginGesture: () => void;
+    endGesture: () => void;
+    displayText: string;
+    formatValue: (normalized: number) => Promise<string>;
+    setValueFromString: (text: string) => void;
+    parseError?: string;
+    _setParseError: (message?: string) => void;
 
     value_to_string: ValueToString<string>;
 
-    constructor({id, name, defaultValue, variants, polyModulationId, flags}: ParamOptions<string> & {
+    constructor({id, name, description, defaultValue, variants, polyModulationId, flags}: ParamOptions<string> & {
       variants: { [key: string]: string },
     }) {
       this.id = id;
       this.name = name;
+      this.description = description;
       this.variants = variants;
       this.polyModulationId = polyModulationId;
       this.flags = flags || {};
@@ -814,18 +1320,28 @@ export module parameters {
       this.value = value;
       this.normalizedValue = normalizedValue;
 
-      this._setNormalizedValue = (value) => {
+      const [valueText, setValueText] = useState<string | undefined>(undefined);
+      this.displayText = valueText ?? this.value_to_string(value);
+
+      const [parseError, setParseError] = useState<string | undefined>(undefined);
+      this.parseError = parseError;
+      this._setParseError = setParseError;
+
+      this._setNormalizedValue = (value, text) => {
+        setValueText(text);
         setValue(this.previewPlain(value));
         setNormalizedValue(value);
       }
 
       this.setValue = (value: string) => {
+        setValueText(undefined);
         setValue(value);
         setNormalizedValue(this.previewNormalized(value));
         sendToPlugin({ParamChange: {id, value: this.previewNormalized(value)}});
       }
 
       this.setNormalizedValue = (value: number) => {
+        setValueText(undefined);
         setValue(this.previewPlain(value));
         setNormalizedValue(value);
         sendToPlugin({ParamChange: {id, value}});
@@ -835,6 +1351,14 @@ export module parameters {
         this.setNormalizedValue(defaultNormalizedValue);
       }
 
+      this.beginGesture = () => sendToPlugin({BeginGesture: id});
+      this.endGesture = () => sendToPlugin({EndGesture: id});
+      this.formatValue = (normalized) => formatValue(id, normalized);
+      this.setValueFromString = (text) => {
+        setParseError(undefined);
+        sendToPlugin({SetParamFromString: {id, text}});
+      };
+
       this.previousStep = (from) => {
         const keys = Object.keys(this.variants);
         const index = keys.findIndex(id => id == from);
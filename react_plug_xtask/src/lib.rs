@@ -1,9 +1,10 @@
 use anyhow::{anyhow, Context};
+use std::ffi::{c_char, CString};
 use std::fs;
 use std::fs::File;
 use std::io::Read;
 use std::path::{Path, PathBuf};
-use std::process::Command;
+use std::process::{Command, Stdio};
 
 pub use anyhow::Result;
 use rayon::iter::{IntoParallelIterator, ParallelIterator};
@@ -45,7 +46,7 @@ pub fn main() -> Result<()> {
 
         chdir_project_root(&packages[0])?;
 
-        std::env::set_current_dir("gui")
+        std::env::set_current_dir(gui_dir(&project_config(Path::new("."))?))
             .context("Could not change to GUI directory. Do you have a /gui directory?")?;
 
         Command::new(package_manager)
@@ -69,20 +70,16 @@ pub fn main() -> Result<()> {
     for package in packages.iter() {
         chdir_project_root(package)?;
 
-        fs::create_dir_all(Path::new("gui/dist"))?;
+        let config = project_config(Path::new("."))?;
+        let gui_dir = gui_dir(&config);
+
+        fs::create_dir_all(gui_dir.join("dist"))?;
 
         println!("Generating bindings...");
 
-        if !Command::new("cargo")
-            .arg("test")
-            .status()
-            .context("Failed to run 'cargo test'")?
-            .success()
-        {
-            return Err(anyhow::anyhow!("Tests failed"));
-        }
+        generate_bindings(package, &config)?;
 
-        std::env::set_current_dir("gui")
+        std::env::set_current_dir(&gui_dir)
             .context("Could not change to GUI directory. Do you have a /gui directory?")?;
 
         if !Path::new("node_modules").exists() {
@@ -144,7 +141,7 @@ pub fn main() -> Result<()> {
                 let mut cwd = get_project_root(&package)
                     .context(format!("Could not change to project root of {}", &package))?;
 
-                cwd.push("gui");
+                cwd.push(gui_dir(&project_config(&cwd)?));
 
                 println!("Starting dev server of {}...", &package);
 
@@ -176,6 +173,107 @@ pub fn main() -> Result<()> {
     }
 }
 
+/// The `[package.metadata.react-plug]` table of the `Cargo.toml` in a project root.
+fn project_config(root: &Path) -> Result<Table> {
+    let manifest =
+        fs::read_to_string(root.join("Cargo.toml")).context("Couldn't read Cargo.toml")?;
+    let manifest = manifest
+        .parse::<Table>()
+        .context("Couldn't parse Cargo.toml")?;
+
+    Ok(manifest
+        .get("package")
+        .and_then(|package| package.get("metadata"))
+        .and_then(|metadata| metadata.get("react-plug"))
+        .and_then(|config| config.as_table())
+        .cloned()
+        .unwrap_or_default())
+}
+
+/// The directory of the GUI, relative to the project root. This is configured using
+/// `gui-dir`, and defaults to `gui`.
+fn gui_dir(config: &Table) -> PathBuf {
+    config
+        .get("gui-dir")
+        .and_then(|dir| dir.as_str())
+        .unwrap_or("gui")
+        .into()
+}
+
+/// Builds the plugin's library, loads it, and calls its bindings entry point, see
+/// `react_plug::bindings::generate_bindings`. The rest of the project config is
+/// passed on as a `react_plug::bindings::BindingsConfig`. This expects the current
+/// working directory to be the project root.
+fn generate_bindings(package: &str, config: &Table) -> Result<()> {
+    let output = Command::new("cargo")
+        .arg("build")
+        .arg("-p")
+        .arg(package)
+        .arg("--lib")
+        .arg("--message-format=json-render-diagnostics")
+        .stderr(Stdio::inherit())
+        .output()
+        .context("Failed to run 'cargo build'")?;
+
+    if !output.status.success() {
+        return Err(anyhow!("Couldn't build {}", package));
+    }
+
+    let library = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+        .filter(|message| {
+            message["reason"] == "compiler-artifact"
+                && message["target"]["kind"]
+                    .as_array()
+                    .is_some_and(|kinds| kinds.iter().any(|kind| kind == "cdylib"))
+        })
+        .filter_map(|message| message["filenames"].as_array().cloned())
+        .flatten()
+        .filter_map(|filename| filename.as_str().map(PathBuf::from))
+        .find(|filename| {
+            filename
+                .to_string_lossy()
+                .ends_with(std::env::consts::DLL_SUFFIX)
+        })
+        .with_context(|| format!("Couldn't find the library of {}, is it a cdylib?", package))?;
+
+    let bindings_dir = config
+        .get("bindings-dir")
+        .and_then(|dir| dir.as_str())
+        .map(PathBuf::from)
+        .unwrap_or_else(|| gui_dir(config).join("src/bindings"));
+
+    let lock_file = config
+        .get("lock-file")
+        .and_then(|file| file.as_str())
+        .unwrap_or("params.lock");
+
+    let root = std::env::current_dir()?;
+    let mut bindings_config = serde_json::to_value(config)?;
+    bindings_config["bindings-dir"] = root.join(bindings_dir).to_string_lossy().into();
+    bindings_config["lock-file"] = root.join(lock_file).to_string_lossy().into();
+    let bindings_config = CString::new(bindings_config.to_string())?;
+
+    // SAFETY: The library is the plugin that was just built, and the entry point is
+    // declared by React-Plug with this exact signature.
+    let generated = unsafe {
+        let library = libloading::Library::new(&library)
+            .with_context(|| format!("Couldn't load {}", library.display()))?;
+        let generate = library
+            .get::<unsafe extern "C" fn(*const c_char) -> bool>(b"react_plug_generate_bindings")
+            .context("Couldn't find the bindings entry point. Does the plugin use React-Plug?")?;
+
+        generate(bindings_config.as_ptr())
+    };
+
+    if !generated {
+        return Err(anyhow!("Couldn't generate bindings"));
+    }
+
+    Ok(())
+}
+
 fn get_project_root(project_name: &String) -> Result<PathBuf> {
     let project_dir = std::env::var("CARGO_MANIFEST_DIR")
         .map(PathBuf::from)
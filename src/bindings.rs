@@ -0,0 +1,686 @@
+use crate::lock::{self, LockedParams, ParamsLock};
+use crate::metadata::ParamInfoFlags;
+use nih_plug::params::persist::PersistentField;
+use nih_plug::params::{ParamFlags, Params};
+use nih_plug::prelude::{BoolParam, Enum, EnumParam, FloatParam, IntParam, Param};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::ffi::{c_char, CStr};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+pub use ts_rs::TS;
+
+#[doc(hidden)]
+pub use inventory;
+
+/// The number of values a float parameter's range is sampled at when its bindings
+/// are read from the parameter itself, see [ParamBindings].
+pub const RANGE_SAMPLES: usize = 257;
+
+/// TypeScript bindings for a parameters struct. This is implemented by
+/// `define_params!` and `#[derive(ReactPlugParams)]`, and used to generate
+/// `Params.ts`, including the bindings of all nested parameter structs.
+pub trait ParamsBindings {
+    /// The TypeScript type of the parameters object.
+    fn ts_type() -> String;
+
+    /// A TypeScript expression that creates the parameters object. `id` maps the ID
+    /// of each parameter, and the key of each persisted field, to the one it has in
+    /// the plugin, e.g. when the struct is nested using an `id_prefix`.
+    fn ts_constructor(&self, id: &dyn Fn(&str) -> String) -> String;
+
+    /// The IDs and descriptions of all parameters that have a description,
+    /// including the nested ones. `id` maps the IDs like in
+    /// [ts_constructor](ParamsBindings::ts_constructor).
+    fn param_descriptions(&self, id: &dyn Fn(&str) -> String) -> Vec<(String, String)>;
+}
+
+impl<P: ParamsBindings> ParamsBindings for Arc<P> {
+    fn ts_type() -> String {
+        P::ts_type()
+    }
+
+    fn ts_constructor(&self, id: &dyn Fn(&str) -> String) -> String {
+        self.as_ref().ts_constructor(id)
+    }
+
+    fn param_descriptions(&self, id: &dyn Fn(&str) -> String) -> Vec<(String, String)> {
+        self.as_ref().param_descriptions(id)
+    }
+}
+
+/// TypeScript bindings for a single parameter, read from the parameter itself. This
+/// is how `#[derive(ReactPlugParams)]` describes parameters whose declarations it
+/// can't see. Float ranges are recreated from [RANGE_SAMPLES] values of the range.
+pub trait ParamBindings {
+    /// The TypeScript type of the parameter.
+    fn ts_type() -> String;
+
+    /// A TypeScript expression that creates the parameter, with the description
+    /// taken from the field's doc comment.
+    fn ts_constructor(&self, id: &str, description: Option<&str>) -> String;
+}
+
+impl ParamBindings for FloatParam {
+    fn ts_type() -> String {
+        "ReactPlug.parameters.FloatParam".to_string()
+    }
+
+    fn ts_constructor(&self, id: &str, description: Option<&str>) -> String {
+        let values = (0..RANGE_SAMPLES)
+            .map(|i| self.preview_plain(i as f32 / (RANGE_SAMPLES - 1) as f32))
+            .collect::<Vec<_>>();
+
+        let mut options =
+            param_options(self, id, description, ts_value(&self.default_plain_value()));
+        options.push(format!(
+            "range: new ReactPlug.ranges.SampledFloatRange({{ values: {} }})",
+            ts_value(&values)
+        ));
+
+        param_constructor("FloatParam", &options)
+    }
+}
+
+impl ParamBindings for IntParam {
+    fn ts_type() -> String {
+        "ReactPlug.parameters.IntParam".to_string()
+    }
+
+    fn ts_constructor(&self, id: &str, description: Option<&str>) -> String {
+        let (start, end) = (self.preview_plain(0.0), self.preview_plain(1.0));
+        let range = if start <= end {
+            format!(
+                "new ReactPlug.ranges.LinearIntRange({{ min: {}, max: {} }})",
+                start, end
+            )
+        } else {
+            format!(
+                "new ReactPlug.ranges.ReversedIntRange(new ReactPlug.ranges.LinearIntRange({{ min: {}, max: {} }}))",
+                end, start
+            )
+        };
+
+        let mut options =
+            param_options(self, id, description, ts_value(&self.default_plain_value()));
+        options.push(format!("range: {}", range));
+
+        param_constructor("IntParam", &options)
+    }
+}
+
+impl ParamBindings for BoolParam {
+    fn ts_type() -> String {
+        "ReactPlug.parameters.BoolParam".to_string()
+    }
+
+    fn ts_constructor(&self, id: &str, description: Option<&str>) -> String {
+        let options = param_options(self, id, description, ts_value(&self.default_plain_value()));
+
+        param_constructor("BoolParam", &options)
+    }
+}
+
+impl<T: Enum + PartialEq + 'static> ParamBindings for EnumParam<T> {
+    fn ts_type() -> String {
+        "ReactPlug.parameters.EnumParam".to_string()
+    }
+
+    fn ts_constructor(&self, id: &str, description: Option<&str>) -> String {
+        let mut options = param_options(
+            self,
+            id,
+            description,
+            ts_enum_value(self.default_plain_value(), None),
+        );
+        options.push(format!("variants: {}", ts_enum_variants_object::<T>(None)));
+
+        param_constructor("EnumParam", &options)
+    }
+}
+
+/// The options all parameter types have in common.
+fn param_options<P: Param>(
+    param: &P,
+    id: &str,
+    description: Option<&str>,
+    default_value: String,
+) -> Vec<String> {
+    let mut options = vec![
+        format!("id: {}", ts_value(id)),
+        format!("name: {}", ts_value(param.name())),
+        format!("defaultValue: {}", default_value),
+        format!("flags: {}", ts_flags(param.flags())),
+    ];
+
+    if let Some(description) = description {
+        options.push(format!("description: {}", ts_value(description)));
+    }
+
+    if !param.unit().is_empty() {
+        options.push(format!("unit: {}", ts_value(param.unit())));
+    }
+
+    if let Some(poly_modulation_id) = param.poly_modulation_id() {
+        options.push(format!("polyModulationId: {}", poly_modulation_id));
+    }
+
+    options
+}
+
+fn param_constructor(ty: &str, options: &[String]) -> String {
+    format!(
+        "new ReactPlug.parameters.{}({{ {} }})",
+        ty,
+        options.join(", ")
+    )
+}
+
+/// The keys and names of an enum's variants, in order. The keys are the variants'
+/// stable IDs if the enum has them. Otherwise, they're the variants' identifiers,
+/// which `define_params!` passes as `idents` for the enums it generates, or their
+/// names if the identifiers aren't known.
+pub fn ts_enum_variants<T: Enum>(
+    idents: Option<&[&'static str]>,
+) -> Vec<(&'static str, &'static str)> {
+    let names = T::variants();
+    let keys = T::ids().or(idents).unwrap_or(names);
+
+    keys.iter().copied().zip(names.iter().copied()).collect()
+}
+
+/// Formats an enum's variants as the TypeScript object an `EnumParam` is created
+/// with, mapping each variant's key to its name.
+pub fn ts_enum_variants_object<T: Enum>(idents: Option<&[&'static str]>) -> String {
+    let variants = ts_enum_variants::<T>(idents)
+        .iter()
+        .map(|(key, name)| format!("{}: {}", ts_value(key), ts_value(name)))
+        .collect::<Vec<_>>();
+
+    format!("{{ {} }}", variants.join(", "))
+}
+
+/// Formats an enum value as the key of its variant, see [ts_enum_variants].
+pub fn ts_enum_value<T: Enum>(value: T, idents: Option<&[&'static str]>) -> String {
+    ts_value(ts_enum_variants::<T>(idents)[value.to_index()].0)
+}
+
+/// Generates the contents of `Params.ts` for the plugin's parameters struct.
+pub fn params_ts<P: ParamsBindings + Params>(params: &P) -> String {
+    format!(
+        r#"import * as ReactPlug from "./react-plug.ts";
+
+export type Params = {};
+
+export type ParamId = {};
+
+export type ParamChange = ReactPlug.ParamChange<ParamId>;
+
+export const createParameters: () => Params = () => ({});
+"#,
+        P::ts_type(),
+        ts_param_id_union(params),
+        params.ts_constructor(&|id| id.to_string())
+    )
+}
+
+/// The IDs of all parameters as a TypeScript union of string literals.
+fn ts_param_id_union<P: Params>(params: &P) -> String {
+    let ids = params
+        .param_map()
+        .iter()
+        .map(|(id, _, _)| ts_value(id))
+        .collect::<Vec<_>>();
+
+    if ids.is_empty() {
+        "never".to_string()
+    } else {
+        ids.join(" | ")
+    }
+}
+
+/// Generates the contents of `Params.ts` for a parameters struct's default values.
+pub fn default_params_ts<P: ParamsBindings + Params + Default>() -> String {
+    params_ts(&P::default())
+}
+
+/// Reads the parameters of a parameters struct's default values for `params.lock`.
+pub fn default_locked_params<P: Params + Default>() -> LockedParams {
+    lock::locked_params(&P::default())
+}
+
+/// The bindings of a root parameters struct. `define_params!` and
+/// `#[derive(ReactPlugParams)]` register one for every struct that isn't nested, so
+/// that [generate_bindings] can find them.
+pub struct BindingsGenerator {
+    /// The name of the parameters struct.
+    pub name: &'static str,
+    /// Prepended to the names of `Params.ts` and `PluginProvider.tsx`. Overrides
+    /// [BindingsConfig::file_prefix] if it isn't empty.
+    pub file_prefix: &'static str,
+    /// Generates `Params.ts`.
+    pub params_ts: fn() -> String,
+    /// Reads the parameters that are written to `params.lock`.
+    pub locked_params: fn() -> LockedParams,
+    /// The IDs of parameters that are allowed to change or disappear from
+    /// `params.lock`, because the plugin migrates them.
+    pub migrated_ids: &'static [&'static str],
+    /// The library code the bindings import, as file names and their contents.
+    pub library: &'static [(&'static str, &'static str)],
+}
+
+impl BindingsGenerator {
+    fn file_prefix<'a>(&'a self, config: &'a BindingsConfig) -> &'a str {
+        if self.file_prefix.is_empty() {
+            &config.file_prefix
+        } else {
+            self.file_prefix
+        }
+    }
+
+    /// Writes `Params.ts` and the library code to the configured directory.
+    pub fn write(&self, config: &BindingsConfig) -> io::Result<()> {
+        let prefix = self.file_prefix(config);
+        let params_ts = (self.params_ts)();
+        let files =
+            std::iter::once(("Params.ts", params_ts.as_str())).chain(self.library.iter().copied());
+
+        fs::create_dir_all(&config.bindings_dir)?;
+
+        for (file, contents) in files {
+            let file = if PREFIXED_MODULES
+                .iter()
+                .any(|module| file.starts_with(module))
+            {
+                format!("{}{}", prefix, file)
+            } else {
+                file.to_string()
+            };
+
+            fs::write(
+                config.bindings_dir.join(file),
+                config.import_style.rewrite_imports(contents, prefix),
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+inventory::collect!(BindingsGenerator);
+
+/// The modules of the bindings that are generated for every parameters struct, and
+/// are named using its file prefix. All other modules are shared.
+const PREFIXED_MODULES: &[&str] = &["Params", "PluginProvider"];
+
+/// The modules the bindings import from each other.
+const BINDINGS_MODULES: &[&str] = &[
+    "Params",
+    "PluginProvider",
+    "react-plug",
+    "GuiMessage",
+    "PluginMessage",
+    "Messages",
+];
+
+/// The message modules `PluginProvider.tsx` imports. They are written as empty
+/// messages if no messages are declared, and the files don't exist yet.
+const MESSAGE_MODULES: &[&str] = &["GuiMessage", "PluginMessage"];
+
+/// How the bindings import each other.
+#[derive(Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum ImportStyle {
+    /// `"./react-plug.ts"`, for bundlers like Vite with `allowImportingTsExtensions`.
+    #[default]
+    TsExtension,
+    /// `"./react-plug"`
+    NoExtension,
+    /// `"./react-plug.js"`, for `"moduleResolution": "NodeNext"`.
+    JsExtension,
+}
+
+impl ImportStyle {
+    fn extension(self) -> &'static str {
+        match self {
+            ImportStyle::TsExtension => ".ts",
+            ImportStyle::NoExtension => "",
+            ImportStyle::JsExtension => ".js",
+        }
+    }
+
+    /// Rewrites the imports between the bindings, which are written as
+    /// `"./module.ts"`, using this style and the file prefix.
+    fn rewrite_imports(self, ts: &str, prefix: &str) -> String {
+        BINDINGS_MODULES.iter().fold(ts.to_string(), |ts, module| {
+            let prefix = if PREFIXED_MODULES.contains(module) {
+                prefix
+            } else {
+                ""
+            };
+
+            ts.replace(
+                &format!("\"./{}.ts\"", module),
+                &format!("\"./{}{}{}\"", prefix, module, self.extension()),
+            )
+        })
+    }
+}
+
+/// Where and how the bindings are written. `cargo xtask` reads this from the
+/// `[package.metadata.react-plug]` table of the plugin's `Cargo.toml`.
+///
+/// ## Example
+///
+/// ```toml
+/// [package.metadata.react-plug]
+/// bindings-dir = "frontend/packages/my-plugin/src/bindings"
+/// file-prefix = "MyPlugin"
+/// import-style = "no-extension"
+/// lock-file = "params.lock"
+/// ```
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case", default)]
+pub struct BindingsConfig {
+    /// The directory the bindings are written to. `cargo xtask` resolves it relative
+    /// to the project root, and defaults to `gui/src/bindings`.
+    pub bindings_dir: PathBuf,
+    /// Prepended to the names of `Params.ts` and `PluginProvider.tsx`.
+    pub file_prefix: String,
+    pub import_style: ImportStyle,
+    /// The lockfile of the parameters, see [ParamsLock]. `cargo xtask` resolves it
+    /// relative to the project root, and defaults to `params.lock`.
+    pub lock_file: PathBuf,
+}
+
+impl Default for BindingsConfig {
+    fn default() -> Self {
+        Self {
+            bindings_dir: PathBuf::from("gui/src/bindings"),
+            file_prefix: String::new(),
+            import_style: ImportStyle::default(),
+            lock_file: PathBuf::from("params.lock"),
+        }
+    }
+}
+
+/// The bindings of the messages declared using `define_messages!`.
+pub struct MessagesBindings {
+    /// Exports `GuiMessage.ts`, `PluginMessage.ts` and the types they use to a
+    /// directory, using ts-rs.
+    pub export: fn(&Path) -> Result<(), ts_rs::ExportError>,
+    /// The contents of `Messages.ts`.
+    pub messages_ts: &'static str,
+}
+
+impl MessagesBindings {
+    fn write(&self, config: &BindingsConfig) -> io::Result<()> {
+        (self.export)(&config.bindings_dir).map_err(io::Error::other)?;
+        fs::write(
+            config.bindings_dir.join("Messages.ts"),
+            config.import_style.rewrite_imports(self.messages_ts, ""),
+        )
+    }
+}
+
+inventory::collect!(MessagesBindings);
+
+/// A type that is exported using ts-rs along with the bindings, see
+/// [export_ts](crate::export_ts).
+pub struct TsExport(pub fn() -> Result<(), ts_rs::ExportError>);
+
+inventory::collect!(TsExport);
+
+/// Exports the TypeScript declarations of types along with the bindings, using their
+/// `#[ts(export_to = "...")]` paths. This is what messages use.
+///
+/// ## Example
+///
+/// ```rust,ignore
+/// #[derive(Serialize, Deserialize, TS)]
+/// #[ts(export, export_to = "../gui/src/bindings/GuiMessage.ts")]
+/// enum GuiMessage {
+///     Ping,
+/// }
+///
+/// react_plug::export_ts!(GuiMessage);
+/// ```
+#[macro_export]
+macro_rules! export_ts {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            $crate::bindings::inventory::submit! {
+                $crate::bindings::TsExport(<$ty as $crate::bindings::TS>::export_all)
+            }
+        )*
+    };
+}
+
+/// Writes the bindings of all root parameters structs, and exports all types
+/// registered using [export_ts](crate::export_ts). Fails without writing anything if
+/// the parameters changed in a way that breaks existing automation, see [ParamsLock].
+pub fn generate_bindings(config: &BindingsConfig) -> io::Result<()> {
+    let mut files = HashMap::new();
+    for generator in inventory::iter::<BindingsGenerator> {
+        let prefix = generator.file_prefix(config);
+        if let Some(other) = files.insert(prefix, generator.name) {
+            return Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                format!(
+                    "{} and {} both write their bindings to {}Params.ts, give one of them a different file prefix",
+                    other, generator.name, prefix
+                ),
+            ));
+        }
+    }
+
+    if files.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            "No parameters struct found, is it declared using define_params!?",
+        ));
+    }
+
+    update_lock(config)?;
+
+    for generator in inventory::iter::<BindingsGenerator> {
+        generator.write(config)?;
+    }
+
+    let mut messages = inventory::iter::<MessagesBindings>.into_iter();
+    match (messages.next(), messages.next()) {
+        (Some(_), Some(_)) => {
+            return Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                "define_messages! can only be used once per plugin",
+            ))
+        }
+        (Some(messages), None) => messages.write(config)?,
+        (None, _) => {
+            for module in MESSAGE_MODULES {
+                let file = config.bindings_dir.join(format!("{}.ts", module));
+                if !file.exists() {
+                    fs::write(file, format!("export type {} = never;\n", module))?;
+                }
+            }
+        }
+    }
+
+    for TsExport(export) in inventory::iter::<TsExport> {
+        export().map_err(io::Error::other)?;
+    }
+
+    Ok(())
+}
+
+/// Compares the parameters of all root parameters structs with the lockfile, and
+/// updates it if nothing broke.
+fn update_lock(config: &BindingsConfig) -> io::Result<()> {
+    let old = ParamsLock::read(&config.lock_file)?;
+    let new = ParamsLock(
+        inventory::iter::<BindingsGenerator>
+            .into_iter()
+            .map(|generator| {
+                let mut params = (generator.locked_params)();
+                if let Some(old_params) = old.0.get(generator.name) {
+                    lock::record_migrations(old_params, &mut params, generator.migrated_ids);
+                }
+
+                (generator.name.to_string(), params)
+            })
+            .collect(),
+    );
+
+    let mut changes = vec![];
+    for (name, old_params) in &old.0 {
+        let generator = inventory::iter::<BindingsGenerator>
+            .into_iter()
+            .find(|generator| generator.name == name);
+
+        match generator {
+            Some(generator) => changes.extend(
+                lock::breaking_changes(old_params, &new.0[name], generator.migrated_ids)
+                    .into_iter()
+                    .map(|change| format!("{}: {}", name, change)),
+            ),
+            None => changes.push(format!(
+                "{} isn't declared anymore, remove it from the lockfile if it was renamed",
+                name
+            )),
+        }
+    }
+
+    if !changes.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!(
+                "The parameters changed in a way that breaks existing automation and saved sessions:\n  {}\nIf the plugin migrates these parameters, add their IDs to `migrated_ids` to update {}. Parameters marked as `migrated` in the lockfile were migrated before, remove the mark to migrate them again",
+                changes.join("\n  "),
+                config.lock_file.display()
+            ),
+        ));
+    }
+
+    if new != old {
+        new.write(&config.lock_file)?;
+    }
+
+    Ok(())
+}
+
+/// The entry point `cargo xtask` calls after loading the plugin's library, see
+/// [generate_bindings]. `config` is a [BindingsConfig] as JSON. Returns whether the
+/// bindings were written.
+///
+/// # Safety
+///
+/// `config` needs to be a valid, null-terminated UTF-8 string.
+#[no_mangle]
+pub unsafe extern "C" fn react_plug_generate_bindings(config: *const c_char) -> bool {
+    let config = CStr::from_ptr(config)
+        .to_str()
+        .map_err(|err| err.to_string())
+        .and_then(|config| {
+            serde_json::from_str::<BindingsConfig>(config).map_err(|err| err.to_string())
+        });
+
+    let result = match config {
+        Ok(config) => generate_bindings(&config).map_err(|err| err.to_string()),
+        Err(err) => Err(format!("Invalid bindings config: {}", err)),
+    };
+
+    match result {
+        Ok(()) => true,
+        Err(err) => {
+            eprintln!("Couldn't generate bindings: {}", err);
+            false
+        }
+    }
+}
+
+/// Formats the fields of a TypeScript object literal or type, one per line.
+pub fn ts_object(fields: &[String]) -> String {
+    ts_lines(fields, "{", "}")
+}
+
+/// Formats the items of a TypeScript array literal, one per line.
+pub fn ts_array(items: &[String]) -> String {
+    ts_lines(items, "[", "]")
+}
+
+fn ts_lines(lines: &[String], open: &str, close: &str) -> String {
+    if lines.is_empty() {
+        return format!("{}{}", open, close);
+    }
+
+    let lines = lines
+        .iter()
+        .map(|line| format!("    {}", line.replace('\n', "\n    ")))
+        .collect::<Vec<_>>()
+        .join(",\n");
+
+    format!("{}\n{}\n{}", open, lines, close)
+}
+
+/// Formats a description as a JSDoc comment, on its own lines in front of the field
+/// it documents.
+pub fn ts_doc_comment(description: &str) -> String {
+    let description = description.replace("*/", "*\\/");
+    if description.contains('\n') {
+        let lines = description
+            .lines()
+            .map(|line| format!(" * {}", line).trim_end().to_string())
+            .collect::<Vec<_>>()
+            .join("\n");
+        format!("/**\n{}\n */\n", lines)
+    } else {
+        format!("/** {} */\n", description)
+    }
+}
+
+/// Formats a value as a TypeScript literal, using its JSON representation.
+pub fn ts_value<T: Serialize + ?Sized>(value: &T) -> String {
+    serde_json::to_string(value).expect("Couldn't serialize value for the bindings")
+}
+
+/// Formats the current value of a persisted field as a TypeScript literal.
+pub fn ts_persisted_value<'a, T, F>(field: &F) -> String
+where
+    F: PersistentField<'a, T>,
+    T: Serialize + Deserialize<'a>,
+{
+    field.map(|value| ts_value(value))
+}
+
+/// Formats a parameter's flags as a TypeScript `ParamFlags` object.
+pub fn ts_flags(flags: ParamFlags) -> String {
+    ts_value(&ParamInfoFlags::from(flags))
+}
+
+/// The ID of a parameter, or the key of a persisted field, inside a struct nested
+/// using `#[nested(id_prefix = "...")]`.
+pub fn prefixed_id(id_prefix: &str, id: &str) -> String {
+    format!("{}_{}", id_prefix, id)
+}
+
+/// The ID of a parameter inside the struct at `index` of a struct array nested using
+/// `#[nested(array)]`.
+pub fn array_id(id: &str, index: usize) -> String {
+    format!("{}_{}", id, index + 1)
+}
+
+/// The inverse of [prefixed_id].
+pub fn strip_id_prefix<'a>(id_prefix: &str, id: &'a str) -> Option<&'a str> {
+    id.strip_prefix(id_prefix)?.strip_prefix('_')
+}
+
+/// The inverse of [array_id], returning the ID and the index.
+pub fn split_array_id(id: &str) -> Option<(&str, usize)> {
+    let (id, number) = id.rsplit_once('_')?;
+    match number.parse::<usize>() {
+        Ok(number) if number > 0 => Some((id, number - 1)),
+        _ => None,
+    }
+}
@@ -0,0 +1,20 @@
+use nih_plug::{nih_error, nih_log, nih_warn};
+use serde::{Deserialize, Serialize};
+
+/// The level of a message logged by the GUI, in increasing order of severity.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LogLevel {
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+/// Writes a message logged by the GUI to nih-plug's logger.
+pub(crate) fn log(level: LogLevel, message: &str) {
+    match level {
+        LogLevel::Debug | LogLevel::Info => nih_log!("[GUI] {}", message),
+        LogLevel::Warn => nih_warn!("[GUI] {}", message),
+        LogLevel::Error => nih_error!("[GUI] {}", message),
+    }
+}
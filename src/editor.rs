@@ -1,17 +1,53 @@
-use crate::{GuiMessage, MessageChannel, ParamChange, PluginMessage};
+use crate::bindings::ParamsBindings;
+use crate::console::{self, LogLevel};
+use crate::history::{History, DEFAULT_HISTORY_SIZE};
+use crate::metadata::param_metadata;
+use crate::midi_learn::{MidiLearn, MidiLearnEvent, MidiLearnRequest};
+use crate::notes::NoteSender;
+use crate::presets::{PresetEvent, PresetManager, PresetRequest};
+use crate::{
+    FormatError, FormattedValue, GuiMessage, MessageChannel, ParamChange, ParamDisplayValue,
+    ParamId, ParseError, PersistedField, PluginMessage,
+};
 use include_dir::Dir;
 use nih_plug::editor::{Editor, ParentWindowHandle};
+use nih_plug::nih_warn;
 use nih_plug::params::Params;
-use nih_plug::prelude::{GuiContext, ParamPtr};
-use nih_plug::{nih_dbg, nih_log, nih_warn};
+use nih_plug::prelude::{GuiContext, ParamPtr, ParamSetter};
 use nih_plug_webview::http::Response;
 use nih_plug_webview::{EventStatus, HTMLSource, KeyboardEvent, MouseEvent, WebViewEditor};
 use serde::de::DeserializeOwned;
 use serde::Serialize;
 use std::any::Any;
+use std::collections::HashMap;
 use std::fmt::Debug;
 use std::marker::PhantomData;
-use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// A minimal editor that displays all parameters. It is shown if the bundled GUI
+/// doesn't contain an `index.html`, or if it was enabled using
+/// [ReactPlugEditor::with_generic_editor].
+static GENERIC_EDITOR: &str = include_str!("generic/index.html");
+
+/// Sends a message to the GUI, see [ReactPlugEditor::with_message_handler].
+pub type PluginMessageSender<PM> =
+    Arc<dyn Fn(PM) -> Result<(), crossbeam_channel::TrySendError<PluginMessage<PM>>> + 'static>;
+
+/// A GUI message that is handled by the methods of a handler `H`. `define_messages!`
+/// implements this for its `GuiMessage` and every `GuiMessageHandler`, see
+/// [ReactPlugEditor::with_handler].
+pub trait DispatchMessage<PM, H> {
+    fn dispatch(self, handler: &H, send: &PluginMessageSender<PM>);
+}
+
+type DisplayValueSource = Arc<dyn Fn() -> f32 + Send + Sync + 'static>;
+
+type MessageHandler<PM, GM> = Arc<dyn Fn(GM, PluginMessageSender<PM>) + Send + Sync + 'static>;
+
+type ParamChangeHandler<PM> =
+    Arc<dyn Fn(ParamChange, PluginMessageSender<PM>) + Send + Sync + 'static>;
 
 pub struct ReactPlugEditor<PM, GM>
 where
@@ -21,8 +57,21 @@ where
     editor: WebViewEditor,
     plugin_msg_channel: MessageChannel<PluginMessage<PM>>,
     dir: &'static Dir<'static>,
+    generic_editor: Arc<AtomicBool>,
     gui_messages: PhantomData<GM>,
     params: Arc<dyn Params>,
+    param_map: Vec<(String, ParamPtr, String)>,
+    param_descriptions: Arc<HashMap<String, String>>,
+    message_handler: Option<MessageHandler<PM, GM>>,
+    param_change_handler: Option<ParamChangeHandler<PM>>,
+    presets: Option<Arc<PresetManager>>,
+    midi_learn: Option<Arc<MidiLearn>>,
+    note_sender: Option<NoteSender>,
+    history_size: usize,
+    log_level: LogLevel,
+    value_text: bool,
+    display_values: Vec<(String, DisplayValueSource)>,
+    display_value_interval: Duration,
 }
 
 impl<PM, GM> ReactPlugEditor<PM, GM>
@@ -32,8 +81,9 @@ where
 {
     pub fn new(params: Arc<impl Params>, dir: &'static Dir, size: (u32, u32)) -> Self {
         let plugin_msg_channel = Arc::new(crossbeam_channel::unbounded());
-        let pm_channel_editor = plugin_msg_channel.clone();
         let param_map = params.param_map();
+        let generic_editor = Arc::new(AtomicBool::new(false));
+        let show_generic_editor = generic_editor.clone();
 
         let editor = if cfg!(rp_dev) {
             WebViewEditor::new(HTMLSource::URL("http://localhost:5173"), size)
@@ -48,117 +98,72 @@ where
 
             let url = HTMLSource::URL(url_scheme);
 
-            WebViewEditor::new(url, size).with_custom_protocol(protocol.parse().unwrap(), |req| {
-                let path = req.uri().path();
+            WebViewEditor::new(url, size).with_custom_protocol(
+                protocol.parse().unwrap(),
+                move |req| {
+                    let path = req.uri().path();
 
-                let path = if path == "/" {
-                    "index.html"
-                } else {
-                    &path[1..]
-                };
+                    let path = if path == "/" {
+                        "index.html"
+                    } else {
+                        &path[1..]
+                    };
 
-                let mime_type = mime_guess::from_path(path)
-                    .first_or_text_plain()
-                    .to_string();
+                    if path == "index.html"
+                        && (show_generic_editor.load(Ordering::Relaxed)
+                            || dir.get_file(path).is_none())
+                    {
+                        return Response::builder()
+                            .header("content-type", "text/html")
+                            .header("Access-Control-Allow-Origin", "*")
+                            .body(GENERIC_EDITOR.as_bytes().into())
+                            .map_err(Into::into);
+                    }
 
-                if let Some(file) = dir.get_file(path) {
-                    let content = file.contents();
+                    let mime_type = mime_guess::from_path(path)
+                        .first_or_text_plain()
+                        .to_string();
 
-                    Response::builder()
-                        .header("content-type", mime_type)
-                        .header("Access-Control-Allow-Origin", "*")
-                        .body(content.into())
-                        .map_err(Into::into)
-                } else {
-                    Response::builder()
-                        .header("content-type", "text/plain")
-                        .header("Access-Control-Allow-Origin", "*")
-                        .body("404 Not Found".as_bytes().into())
-                        .map_err(Into::into)
-                }
-            })
-        }
-        .with_event_loop(move |ctx, setter, _window| unsafe {
-            while let Ok(value) = ctx.next_event() {
-                if let Ok(message) = serde_json::from_value::<GuiMessage<GM>>(value.clone()) {
-                    match message {
-                        GuiMessage::Init => {
-                            param_map.iter().for_each(|(id, param, _)| {
-                                pm_channel_editor
-                                    .0
-                                    .send(PluginMessage::ParamChange(ParamChange {
-                                        id: id.to_string(),
-                                        value: param.modulated_normalized_value(),
-                                    }))
-                                    .unwrap()
-                            });
-                        }
-                        GuiMessage::ParamChange(param_change) => {
-                            let param = if let Some(param) =
-                                param_map.iter().find(|(id, _, _)| id == &param_change.id)
-                            {
-                                param.1
-                            } else {
-                                nih_warn!("Couldn't find parameter with id: {}", param_change.id);
-                                continue;
-                            };
-
-                            match param {
-                                ParamPtr::FloatParam(p) => setter.begin_set_parameter(&*p),
-                                ParamPtr::IntParam(p) => setter.begin_set_parameter(&*p),
-                                ParamPtr::BoolParam(p) => setter.begin_set_parameter(&*p),
-                                ParamPtr::EnumParam(p) => setter.begin_set_parameter(&*p),
-                            }
-
-                            match param {
-                                ParamPtr::FloatParam(p) => {
-                                    setter.set_parameter_normalized(&*p, param_change.value)
-                                }
-                                ParamPtr::IntParam(p) => {
-                                    setter.set_parameter_normalized(&*p, param_change.value)
-                                }
-                                ParamPtr::BoolParam(p) => {
-                                    setter.set_parameter_normalized(&*p, param_change.value)
-                                }
-                                ParamPtr::EnumParam(p) => {
-                                    setter.set_parameter_normalized(&*p, param_change.value)
-                                }
-                            }
-
-                            match param {
-                                ParamPtr::FloatParam(p) => setter.end_set_parameter(&*p),
-                                ParamPtr::IntParam(p) => setter.end_set_parameter(&*p),
-                                ParamPtr::BoolParam(p) => setter.end_set_parameter(&*p),
-                                ParamPtr::EnumParam(p) => setter.end_set_parameter(&*p),
-                            }
-                        }
-                        GuiMessage::Message(message) => {}
+                    if let Some(file) = dir.get_file(path) {
+                        let content = file.contents();
+
+                        Response::builder()
+                            .header("content-type", mime_type)
+                            .header("Access-Control-Allow-Origin", "*")
+                            .body(content.into())
+                            .map_err(Into::into)
+                    } else {
+                        Response::builder()
+                            .header("content-type", "text/plain")
+                            .header("Access-Control-Allow-Origin", "*")
+                            .body("404 Not Found".as_bytes().into())
+                            .map_err(Into::into)
                     }
-                } else {
-                    nih_warn!("Couldn't deserialize message from GUI: {:?}", value);
-                }
-            }
-            while !pm_channel_editor.1.is_empty() {
-                let message = pm_channel_editor.1.recv().unwrap();
-                let message_json = serde_json::to_value(&message);
-                if let Ok(message_json) = message_json {
-                    ctx.send_json(message_json);
-                } else {
-                    nih_warn!(
-                        r#"Message couldn't be sent to GUI! Couldn't serialize {:?}"#,
-                        message
-                    );
-                }
-            }
-        });
+                },
+            )
+        };
 
         Self {
             editor,
             plugin_msg_channel,
             dir,
+            generic_editor,
             gui_messages: PhantomData,
             params,
+            param_map,
+            param_descriptions: Arc::new(HashMap::new()),
+            message_handler: None,
+            param_change_handler: None,
+            presets: None,
+            midi_learn: None,
+            note_sender: None,
+            history_size: DEFAULT_HISTORY_SIZE,
+            log_level: LogLevel::Info,
+            value_text: false,
+            display_values: Vec::new(),
+            display_value_interval: Duration::from_millis(16),
         }
+        .with_gui_event_loop()
     }
 
     pub fn with_message_handler(
@@ -173,96 +178,156 @@ where
             + Sync
             + 'static,
     ) -> Self {
-        let pm_channel = self.plugin_msg_channel.clone();
-        let param_map = self.params.param_map();
+        self.message_handler = Some(Arc::new(handler));
+        self.with_gui_event_loop()
+    }
 
-        self.editor = self
-            .editor
-            .with_event_loop(move |ctx, setter, _window| unsafe {
-                while let Ok(value) = ctx.next_event() {
-                    if let Ok(message) = serde_json::from_value::<GuiMessage<GM>>(value.clone()) {
-                        match message {
-                            GuiMessage::Init => {
-                                param_map.iter().for_each(|(id, param, _)| {
-                                    pm_channel
-                                        .0
-                                        .send(PluginMessage::ParamChange(ParamChange {
-                                            id: id.to_string(),
-                                            value: param.modulated_normalized_value(),
-                                        }))
-                                        .unwrap()
-                                });
-                            }
-                            GuiMessage::ParamChange(param_change) => {
-                                let param = if let Some(param) =
-                                    param_map.iter().find(|(id, _, _)| id == &param_change.id)
-                                {
-                                    param.1
-                                } else {
-                                    nih_warn!(
-                                        "Couldn't find parameter with id: {}",
-                                        param_change.id
-                                    );
-                                    continue;
-                                };
-
-                                match param {
-                                    ParamPtr::FloatParam(p) => setter.begin_set_parameter(&*p),
-                                    ParamPtr::IntParam(p) => setter.begin_set_parameter(&*p),
-                                    ParamPtr::BoolParam(p) => setter.begin_set_parameter(&*p),
-                                    ParamPtr::EnumParam(p) => setter.begin_set_parameter(&*p),
-                                }
-
-                                match param {
-                                    ParamPtr::FloatParam(p) => {
-                                        setter.set_parameter_normalized(&*p, param_change.value)
-                                    }
-                                    ParamPtr::IntParam(p) => {
-                                        setter.set_parameter_normalized(&*p, param_change.value)
-                                    }
-                                    ParamPtr::BoolParam(p) => {
-                                        setter.set_parameter_normalized(&*p, param_change.value)
-                                    }
-                                    ParamPtr::EnumParam(p) => {
-                                        setter.set_parameter_normalized(&*p, param_change.value)
-                                    }
-                                }
-
-                                match param {
-                                    ParamPtr::FloatParam(p) => setter.end_set_parameter(&*p),
-                                    ParamPtr::IntParam(p) => setter.end_set_parameter(&*p),
-                                    ParamPtr::BoolParam(p) => setter.end_set_parameter(&*p),
-                                    ParamPtr::EnumParam(p) => setter.end_set_parameter(&*p),
-                                }
-                            }
-                            GuiMessage::Message(message) => {
-                                let sender = pm_channel.0.clone();
-                                handler(
-                                    message,
-                                    Arc::new(move |pm| sender.try_send(PluginMessage::Message(pm))),
-                                )
-                            }
-                        }
-                    } else {
-                        nih_warn!("Couldn't deserialize message from GUI: {:?}", value);
-                    }
-                }
-                while !pm_channel.1.is_empty() {
-                    let message = pm_channel.1.recv().unwrap();
-                    let message_json = serde_json::to_value(&message);
-                    if let Ok(message_json) = message_json {
-                        ctx.send_json(message_json);
-                    } else {
-                        nih_warn!(
-                            r#"Message couldn't be sent to GUI! Couldn't serialize {:?}"#,
-                            message
-                        );
-                    }
-                }
-            });
+    /// Handles GUI messages by calling the method of `handler` for each message,
+    /// e.g. the `GuiMessageHandler` generated by `define_messages!`.
+    pub fn with_handler<H>(self, handler: H) -> Self
+    where
+        GM: DispatchMessage<PM, H>,
+        H: Send + Sync + 'static,
+    {
+        self.with_message_handler(move |message, send| message.dispatch(&handler, &send))
+    }
+
+    /// Calls `handler` for every parameter change made from the GUI, with the ID
+    /// parsed as `I`, e.g. the `ExampleParamsId` of `ExampleParams`. Changes of
+    /// parameters that `I` doesn't know are skipped.
+    pub fn with_param_change_handler<I>(
+        mut self,
+        handler: impl Fn(ParamChange<I>, PluginMessageSender<PM>) + Send + Sync + 'static,
+    ) -> Self
+    where
+        I: ParamId + 'static,
+    {
+        self.param_change_handler = Some(Arc::new(move |change: ParamChange, send| {
+            match change.into_typed::<I>() {
+                Ok(change) => handler(change, send),
+                Err(err) => nih_warn!("Couldn't handle parameter change: {}", err),
+            }
+        }));
+        self.with_gui_event_loop()
+    }
+
+    /// Sends the descriptions of the parameters to the GUI as part of their metadata,
+    /// e.g. for the generic editor. `params` needs to be defined using
+    /// `define_params!` or derive `ReactPlugParams`, which read the descriptions
+    /// from doc comments and `description` fields.
+    pub fn with_descriptions(mut self, params: &impl ParamsBindings) -> Self {
+        self.param_descriptions = Arc::new(
+            params
+                .param_descriptions(&|id| id.to_string())
+                .into_iter()
+                .collect(),
+        );
+        self.with_gui_event_loop()
+    }
+
+    /// Enables preset management through the given [PresetManager]. The GUI can
+    /// then list, load, save, rename and delete presets, e.g. using `usePresets()`.
+    pub fn with_presets(mut self, presets: PresetManager) -> Self {
+        self.presets = Some(Arc::new(presets));
+        self.with_gui_event_loop()
+    }
+
+    /// Enables MIDI learn. The GUI can then map MIDI CCs to parameters. While the
+    /// editor is open, it sets the parameters to the mapped CCs that
+    /// [MidiLearn::handle_event] received.
+    pub fn with_midi_learn(mut self, midi_learn: Arc<MidiLearn>) -> Self {
+        midi_learn.set_param_ids(self.param_map.iter().map(|(id, _, _)| id.clone()));
+        self.midi_learn = Some(midi_learn);
+        self.with_gui_event_loop()
+    }
+
+    /// Forwards note events sent by the GUI to the audio thread, where they can be
+    /// received using the matching [NoteReceiver](crate::notes::NoteReceiver).
+    pub fn with_note_sender(mut self, note_sender: NoteSender) -> Self {
+        self.note_sender = Some(note_sender);
+        self.with_gui_event_loop()
+    }
+
+    /// Sets how many parameter edits made from the GUI can be undone. Setting this
+    /// to `0` disables the undo history.
+    pub fn with_history_size(mut self, size: usize) -> Self {
+        self.history_size = size;
+        self.with_gui_event_loop()
+    }
+
+    /// Sets the minimum level of messages logged by the GUI that are written to
+    /// nih-plug's logger. Defaults to [LogLevel::Info].
+    pub fn with_log_level(mut self, level: LogLevel) -> Self {
+        self.log_level = level;
+        self.with_gui_event_loop()
+    }
+
+    /// Sends the display text of a parameter, as formatted by its `value_to_string`
+    /// function, along with every parameter change. This way, the GUI can display
+    /// values exactly like the host does, even for custom formatters.
+    pub fn with_value_text(mut self, value_text: bool) -> Self {
+        self.value_text = value_text;
+        self.with_gui_event_loop()
+    }
+
+    /// Shows a minimal, generic editor that lists all parameters instead of the
+    /// bundled GUI. The generic editor is also shown if the bundled GUI doesn't
+    /// contain an `index.html`. This has no effect when using the dev server.
+    pub fn with_generic_editor(self, enabled: bool) -> Self {
+        self.generic_editor.store(enabled, Ordering::Relaxed);
         self
     }
 
+    /// Periodically sends a value read from the audio thread's side of a parameter to
+    /// the GUI as a [ParamDisplayValue], e.g. for animating controls while a
+    /// parameter is being smoothed. The value is only sent when it changes.
+    ///
+    /// ## Example
+    ///
+    /// ```rust,ignore
+    /// let params = self.params.clone();
+    ///
+    /// editor.with_display_value("cutoff", move || params.cutoff.smoothed.previous_value())
+    /// ```
+    pub fn with_display_value(
+        mut self,
+        id: impl Into<String>,
+        value: impl Fn() -> f32 + Send + Sync + 'static,
+    ) -> Self {
+        self.display_values.push((id.into(), Arc::new(value)));
+        self.with_gui_event_loop()
+    }
+
+    /// Periodically sends the modulated plain values of these parameters to the GUI
+    /// as [ParamDisplayValue]s. See [ReactPlugEditor::with_display_value].
+    pub fn with_modulated_display_values<'a>(
+        mut self,
+        ids: impl IntoIterator<Item = &'a str>,
+    ) -> Self {
+        for id in ids {
+            if let Some((_, param, _)) = self
+                .param_map
+                .iter()
+                .find(|(param_id, _, _)| param_id == id)
+            {
+                let param = *param;
+                self.display_values.push((
+                    id.to_string(),
+                    Arc::new(move || unsafe { param.modulated_plain_value() }),
+                ));
+            } else {
+                nih_warn!("Couldn't find parameter with id: {}", id);
+            }
+        }
+        self.with_gui_event_loop()
+    }
+
+    /// Sets how often display values are sent to the GUI. Defaults to 16 ms.
+    pub fn with_display_value_interval(mut self, interval: Duration) -> Self {
+        self.display_value_interval = interval;
+        self.with_gui_event_loop()
+    }
+
     pub fn with_background_color(mut self, background_color: (u8, u8, u8, u8)) -> Self {
         self.editor = self.editor.with_background_color(background_color);
         self
@@ -292,6 +357,434 @@ where
     pub fn with_protocol(mut self, protocol: &'static str) -> Self {
         todo!()
     }
+
+    /// (Re-)installs the event loop of the underlying [WebViewEditor], so that it
+    /// reflects the current configuration of this editor.
+    fn with_gui_event_loop(mut self) -> Self {
+        let handler = GuiEventHandler {
+            pm_channel: self.plugin_msg_channel.clone(),
+            params: self.params.clone(),
+            param_map: self.param_map.clone(),
+            param_descriptions: self.param_descriptions.clone(),
+            message_handler: self.message_handler.clone(),
+            param_change_handler: self.param_change_handler.clone(),
+            presets: self.presets.clone(),
+            midi_learn: self.midi_learn.clone(),
+            note_sender: self.note_sender.clone(),
+            history: Mutex::new(History::new(self.history_size)),
+            log_level: self.log_level,
+            value_text: self.value_text,
+            display_values: DisplayValues {
+                sources: self.display_values.clone(),
+                interval: self.display_value_interval,
+                last_update: Mutex::new(None),
+                last_values: Mutex::new(vec![None; self.display_values.len()]),
+            },
+        };
+
+        self.editor = self
+            .editor
+            .with_event_loop(move |ctx, setter, _window| unsafe {
+                while let Ok(value) = ctx.next_event() {
+                    if let Ok(message) = serde_json::from_value::<GuiMessage<GM>>(value.clone()) {
+                        handler.handle(message, &setter);
+                    } else {
+                        nih_warn!("Couldn't deserialize message from GUI: {:?}", value);
+                    }
+                }
+                handler.learn_midi_cc();
+                handler.apply_midi_ccs(&setter);
+                handler.send_display_values();
+                while !handler.pm_channel.1.is_empty() {
+                    let message = handler.pm_channel.1.recv().unwrap();
+                    let message_json = serde_json::to_value(&message);
+                    if let Ok(message_json) = message_json {
+                        ctx.send_json(message_json);
+                    } else {
+                        nih_warn!(
+                            r#"Message couldn't be sent to GUI! Couldn't serialize {:?}"#,
+                            message
+                        );
+                    }
+                }
+            });
+        self
+    }
+}
+
+/// Everything the editor's event loop needs in order to handle messages from the GUI.
+struct GuiEventHandler<PM, GM> {
+    pm_channel: MessageChannel<PluginMessage<PM>>,
+    params: Arc<dyn Params>,
+    param_map: Vec<(String, ParamPtr, String)>,
+    param_descriptions: Arc<HashMap<String, String>>,
+    message_handler: Option<MessageHandler<PM, GM>>,
+    param_change_handler: Option<ParamChangeHandler<PM>>,
+    presets: Option<Arc<PresetManager>>,
+    midi_learn: Option<Arc<MidiLearn>>,
+    note_sender: Option<NoteSender>,
+    history: Mutex<History>,
+    log_level: LogLevel,
+    value_text: bool,
+    display_values: DisplayValues,
+}
+
+/// The display values that are periodically sent to the GUI.
+struct DisplayValues {
+    sources: Vec<(String, DisplayValueSource)>,
+    interval: Duration,
+    last_update: Mutex<Option<Instant>>,
+    last_values: Mutex<Vec<Option<f32>>>,
+}
+
+impl<PM, GM> GuiEventHandler<PM, GM>
+where
+    PM: Send + 'static,
+{
+    unsafe fn handle(&self, message: GuiMessage<GM>, setter: &ParamSetter) {
+        match message {
+            GuiMessage::FormatValue { id, normalized } => {
+                if let Some(param) = self.find_param(&id) {
+                    let text = param.normalized_value_to_string(normalized, false);
+                    self.send(PluginMessage::FormattedValue(FormattedValue {
+                        id,
+                        normalized,
+                        text,
+                    }));
+                } else {
+                    nih_warn!("Couldn't find parameter with id: {}", id);
+                    self.send(PluginMessage::FormatError(FormatError {
+                        message: format!("Couldn't find parameter with id: {}", id),
+                        id,
+                        normalized,
+                    }));
+                }
+            }
+            GuiMessage::Init => {
+                self.send(PluginMessage::ParamInfo(param_metadata(
+                    &self.param_map,
+                    &self.param_descriptions,
+                )));
+                self.param_map.iter().for_each(|(id, param, _)| {
+                    self.send(PluginMessage::ParamChange(param_change(
+                        id,
+                        *param,
+                        param.modulated_normalized_value(),
+                        self.value_text,
+                    )))
+                });
+                persisted_fields(self.params.as_ref())
+                    .for_each(|field| self.send(PluginMessage::PersistedField(field)));
+                self.send_history_state();
+            }
+            GuiMessage::PersistedField(field) => {
+                if !self.params.serialize_fields().contains_key(&field.key) {
+                    nih_warn!("Couldn't find persisted field with key: {}", field.key);
+                    return;
+                }
+
+                // Writing the field through the wrapper, instead of only deserializing
+                // it into the parameters, lets the host know that the state changed
+                let mut state = setter.raw_context.get_state();
+                state
+                    .fields
+                    .insert(field.key.clone(), field.value.to_string());
+                setter.raw_context.set_state(state);
+                self.send(PluginMessage::PersistedField(field));
+            }
+            GuiMessage::ParamChange(param_change) => {
+                if let Some(param) = self.find_param(&param_change.id) {
+                    self.change_param(setter, &param_change.id, param, param_change.value);
+                } else {
+                    nih_warn!("Couldn't find parameter with id: {}", param_change.id);
+                }
+            }
+            GuiMessage::SetParamFromString { id, text } => {
+                let param = if let Some(param) = self.find_param(&id) {
+                    param
+                } else {
+                    nih_warn!("Couldn't find parameter with id: {}", id);
+                    return;
+                };
+
+                if let Some(value) = param.string_to_normalized_value(&text) {
+                    self.change_param(setter, &id, param, value);
+                } else {
+                    let message = format!(
+                        r#"Couldn't parse "{}" as a value for {}"#,
+                        text,
+                        param.name()
+                    );
+                    self.send(PluginMessage::ParseError(ParseError { id, text, message }));
+                }
+            }
+            GuiMessage::BeginGesture(id) => {
+                if let Some(param) = self.find_param(&id) {
+                    setter.raw_context.raw_begin_set_parameter(param);
+                    self.history
+                        .lock()
+                        .unwrap()
+                        .begin_gesture(&id, param.unmodulated_normalized_value());
+                } else {
+                    nih_warn!("Couldn't find parameter with id: {}", id);
+                }
+            }
+            GuiMessage::EndGesture(id) => {
+                if let Some(param) = self.find_param(&id) {
+                    setter.raw_context.raw_end_set_parameter(param);
+                    self.history.lock().unwrap().end_gesture(&id);
+                    self.send_history_state();
+                } else {
+                    nih_warn!("Couldn't find parameter with id: {}", id);
+                }
+            }
+            GuiMessage::Undo => {
+                let edit = self.history.lock().unwrap().undo();
+                if let Some(edit) = edit {
+                    if let Some(param) = self.find_param(&edit.id) {
+                        set_parameter(setter, param, edit.from);
+                    }
+                }
+                self.send_history_state();
+            }
+            GuiMessage::Redo => {
+                let edit = self.history.lock().unwrap().redo();
+                if let Some(edit) = edit {
+                    if let Some(param) = self.find_param(&edit.id) {
+                        set_parameter(setter, param, edit.to);
+                    }
+                }
+                self.send_history_state();
+            }
+            GuiMessage::Log { level, message } => {
+                if level >= self.log_level {
+                    console::log(level, &message);
+                }
+            }
+            GuiMessage::MidiLearn(request) => self.handle_midi_learn_request(request),
+            GuiMessage::Note(event) => {
+                if let Some(note_sender) = &self.note_sender {
+                    if !note_sender.send(event) {
+                        nih_warn!("Note queue is full, dropping {:?}", event);
+                    }
+                } else {
+                    nih_warn!("Received a note event, but no note sender was set for this editor");
+                }
+            }
+            GuiMessage::Preset(request) => self.handle_preset_request(request, setter),
+            GuiMessage::Message(message) => {
+                if let Some(handler) = &self.message_handler {
+                    handler(message, self.message_sender())
+                }
+            }
+        }
+    }
+
+    fn handle_preset_request(&self, request: PresetRequest, setter: &ParamSetter) {
+        let presets = if let Some(presets) = &self.presets {
+            presets
+        } else {
+            nih_warn!("Received a preset request, but presets aren't enabled for this editor");
+            self.send(PluginMessage::Preset(PresetEvent::Error(
+                "Presets aren't enabled for this plugin".to_string(),
+            )));
+            return;
+        };
+
+        let result = match request {
+            PresetRequest::List => Ok(()),
+            PresetRequest::Load(preset) => presets.load(&preset).map(|state| {
+                setter.raw_context.set_state(state);
+                self.send(PluginMessage::Preset(PresetEvent::Loaded(preset)));
+            }),
+            PresetRequest::Save(name) => presets.save(&name, setter.raw_context.get_state()),
+            PresetRequest::Rename { from, to } => presets.rename(&from, &to),
+            PresetRequest::Delete(name) => presets.delete(&name),
+        };
+
+        if let Err(err) = result {
+            nih_warn!("Preset request failed: {}", err);
+            self.send(PluginMessage::Preset(PresetEvent::Error(err.to_string())));
+        }
+
+        self.send(PluginMessage::Preset(PresetEvent::List(presets.list())));
+    }
+
+    /// Sets a parameter to a value from the GUI and records the change in the
+    /// history. If a gesture is in progress for the parameter, the change becomes
+    /// part of it.
+    unsafe fn change_param(&self, setter: &ParamSetter, id: &str, param: ParamPtr, value: f32) {
+        let mut history = self.history.lock().unwrap();
+        history.record(id, param.unmodulated_normalized_value(), value);
+
+        if history.in_gesture(id) {
+            setter
+                .raw_context
+                .raw_set_parameter_normalized(param, value);
+        } else {
+            drop(history);
+            set_parameter(setter, param, value);
+            self.send_history_state();
+        }
+
+        if let Some(handler) = &self.param_change_handler {
+            let param_change = ParamChange {
+                id: id.to_string(),
+                value,
+                text: None,
+            };
+            handler(param_change, self.message_sender());
+        }
+    }
+
+    /// Sends the plugin's own messages to the GUI, for the message handlers.
+    fn message_sender(&self) -> PluginMessageSender<PM> {
+        let sender = self.pm_channel.0.clone();
+        Arc::new(move |pm| sender.try_send(PluginMessage::Message(pm)))
+    }
+
+    fn handle_midi_learn_request(&self, request: MidiLearnRequest) {
+        let midi_learn = if let Some(midi_learn) = &self.midi_learn {
+            midi_learn
+        } else {
+            nih_warn!(
+                "Received a MIDI learn request, but MIDI learn isn't enabled for this editor"
+            );
+            return;
+        };
+
+        match request {
+            MidiLearnRequest::List => {}
+            MidiLearnRequest::Learn(id) => {
+                if let Err(err) = midi_learn.learn(id) {
+                    nih_warn!("Couldn't learn MIDI CC: {}", err);
+                }
+            }
+            MidiLearnRequest::Cancel => midi_learn.cancel(),
+            MidiLearnRequest::Forget(id) => midi_learn.forget(&id),
+        }
+
+        self.send(PluginMessage::MidiLearn(MidiLearnEvent::Learning(
+            midi_learn.learning(),
+        )));
+        self.send_midi_mappings(midi_learn);
+    }
+
+    /// Learns the CC that was received for the parameter waiting for one, if any.
+    fn learn_midi_cc(&self) {
+        let midi_learn = if let Some(midi_learn) = &self.midi_learn {
+            midi_learn
+        } else {
+            return;
+        };
+
+        if let Some((cc, id)) = midi_learn.try_learn() {
+            self.send(PluginMessage::MidiLearn(MidiLearnEvent::Learned { cc, id }));
+            self.send(PluginMessage::MidiLearn(MidiLearnEvent::Learning(None)));
+            self.send_midi_mappings(midi_learn);
+        }
+    }
+
+    /// Sets the parameters that CCs received by the audio thread are mapped to.
+    unsafe fn apply_midi_ccs(&self, setter: &ParamSetter) {
+        let midi_learn = if let Some(midi_learn) = &self.midi_learn {
+            midi_learn
+        } else {
+            return;
+        };
+
+        while let Some((id, value)) = midi_learn.next_mapped_cc() {
+            if let Some(param) = self.find_param(&id) {
+                set_parameter(setter, param, value);
+            }
+        }
+    }
+
+    fn send_midi_mappings(&self, midi_learn: &MidiLearn) {
+        self.send(PluginMessage::MidiLearn(MidiLearnEvent::Mappings(
+            midi_learn.mappings(),
+        )));
+    }
+
+    /// Sends the display values that have changed, if the interval has passed.
+    fn send_display_values(&self) {
+        let display_values = &self.display_values;
+        if display_values.sources.is_empty() {
+            return;
+        }
+
+        let now = Instant::now();
+        let mut last_update = display_values.last_update.lock().unwrap();
+        if matches!(*last_update, Some(last_update) if now - last_update < display_values.interval)
+        {
+            return;
+        }
+        *last_update = Some(now);
+
+        let mut last_values = display_values.last_values.lock().unwrap();
+        for ((id, source), last_value) in display_values.sources.iter().zip(last_values.iter_mut())
+        {
+            let value = source();
+            if *last_value != Some(value) {
+                *last_value = Some(value);
+                self.send(PluginMessage::ParamDisplayValue(ParamDisplayValue {
+                    id: id.clone(),
+                    value,
+                }));
+            }
+        }
+    }
+
+    fn send_history_state(&self) {
+        let state = self.history.lock().unwrap().state();
+        self.send(PluginMessage::History(state));
+    }
+
+    fn find_param(&self, id: &str) -> Option<ParamPtr> {
+        self.param_map
+            .iter()
+            .find(|(param_id, _, _)| param_id == id)
+            .map(|(_, param, _)| *param)
+    }
+
+    fn send(&self, message: PluginMessage<PM>) {
+        self.pm_channel
+            .0
+            .send(message)
+            .expect("Couldn't send message through internal channel!");
+    }
+}
+
+/// Creates a [ParamChange] for a parameter, optionally including its display text.
+unsafe fn param_change(id: &str, param: ParamPtr, value: f32, with_text: bool) -> ParamChange {
+    ParamChange {
+        id: id.to_string(),
+        value,
+        text: with_text.then(|| param.normalized_value_to_string(value, false)),
+    }
+}
+
+/// All persisted fields of the plugin's parameters.
+fn persisted_fields(params: &dyn Params) -> impl Iterator<Item = PersistedField> {
+    params
+        .serialize_fields()
+        .into_iter()
+        .filter_map(|(key, json)| match serde_json::from_str(&json) {
+            Ok(value) => Some(PersistedField { key, value }),
+            Err(err) => {
+                nih_warn!("Couldn't read persisted field {}: {}", key, err);
+                None
+            }
+        })
+}
+
+/// Sets a parameter as a single gesture.
+unsafe fn set_parameter(setter: &ParamSetter, param: ParamPtr, normalized: f32) {
+    setter.raw_context.raw_begin_set_parameter(param);
+    setter
+        .raw_context
+        .raw_set_parameter_normalized(param, normalized);
+    setter.raw_context.raw_end_set_parameter(param);
 }
 
 impl<PM, GM> Editor for ReactPlugEditor<PM, GM>
@@ -304,7 +797,12 @@ where
         parent: ParentWindowHandle,
         context: Arc<dyn GuiContext>,
     ) -> Box<dyn Any + Send> {
-        self.editor.spawn(parent, context)
+        let handle = self.editor.spawn(parent, context);
+
+        match &self.midi_learn {
+            Some(midi_learn) => Box::new((handle, midi_learn.open_editor())),
+            None => handle,
+        }
     }
 
     fn size(&self) -> (u32, u32) {
@@ -316,12 +814,24 @@ where
     }
 
     fn param_value_changed(&self, id: &str, normalized_value: f32) {
-        self.plugin_msg_channel
-            .0
-            .send(PluginMessage::ParamChange(ParamChange {
+        let param_change = match self
+            .param_map
+            .iter()
+            .find(|(param_id, _, _)| param_id == id)
+        {
+            Some((_, param, _)) => unsafe {
+                param_change(id, *param, normalized_value, self.value_text)
+            },
+            None => ParamChange {
                 id: id.to_string(),
                 value: normalized_value,
-            }))
+                text: None,
+            },
+        };
+
+        self.plugin_msg_channel
+            .0
+            .send(PluginMessage::ParamChange(param_change))
             .expect("Couldn't send parameter update message through internal channel!");
     }
 
@@ -330,7 +840,17 @@ where
     }
 
     fn param_values_changed(&self) {
-        // TODO: Does this need to be handled too?
+        // This is called after the plugin's state has been restored, e.g. when a preset
+        // was loaded, so all parameters are sent to the GUI again
+        self.param_map.iter().for_each(|(id, param, _)| unsafe {
+            self.param_value_changed(id, param.modulated_normalized_value())
+        });
+        persisted_fields(self.params.as_ref()).for_each(|field| {
+            self.plugin_msg_channel
+                .0
+                .send(PluginMessage::PersistedField(field))
+                .expect("Couldn't send persisted field message through internal channel!");
+        });
     }
 }
 
@@ -343,3 +863,28 @@ where
         Some(Box::new(editor))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nih_plug::prelude::{FloatParam, FloatRange};
+
+    /// Parameters without any of react-plug's derives.
+    #[derive(Params)]
+    struct BareParams {
+        #[id = "gain"]
+        gain: FloatParam,
+    }
+
+    static EDITOR_DIR: Dir = Dir::new("", &[]);
+
+    #[test]
+    fn editor_accepts_plain_params() {
+        let params = Arc::new(BareParams {
+            gain: FloatParam::new("Gain", 1.0, FloatRange::Linear { min: 0.0, max: 1.0 }),
+        });
+
+        let editor = ReactPlugEditor::<(), ()>::new(params, &EDITOR_DIR, (800, 600));
+        assert_eq!(editor.size(), (800, 600));
+    }
+}
@@ -0,0 +1,165 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+
+/// The default number of edits that can be undone.
+pub const DEFAULT_HISTORY_SIZE: usize = 100;
+
+/// Whether there is something to undo or redo. This is sent to the GUI whenever the
+/// history changes.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HistoryState {
+    pub can_undo: bool,
+    pub can_redo: bool,
+}
+
+/// A single parameter edit made from the GUI, using normalized values.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Edit {
+    pub id: String,
+    pub from: f32,
+    pub to: f32,
+}
+
+/// The undo/redo history of parameter edits made from the GUI. All changes made
+/// between the beginning and the end of a gesture are grouped into a single [Edit].
+#[derive(Debug)]
+pub struct History {
+    undo_stack: VecDeque<Edit>,
+    redo_stack: Vec<Edit>,
+    gestures: HashMap<String, Edit>,
+    size: usize,
+}
+
+impl History {
+    /// Creates a history that keeps at most `size` edits.
+    pub fn new(size: usize) -> Self {
+        Self {
+            undo_stack: VecDeque::new(),
+            redo_stack: Vec::new(),
+            gestures: HashMap::new(),
+            size,
+        }
+    }
+
+    /// Starts a gesture for a parameter with the given normalized value.
+    pub fn begin_gesture(&mut self, id: &str, value: f32) {
+        self.gestures.insert(
+            id.to_string(),
+            Edit {
+                id: id.to_string(),
+                from: value,
+                to: value,
+            },
+        );
+    }
+
+    /// Whether a gesture is currently in progress for a parameter.
+    pub fn in_gesture(&self, id: &str) -> bool {
+        self.gestures.contains_key(id)
+    }
+
+    /// Records a parameter change. If a gesture is in progress for the parameter,
+    /// the change becomes part of that gesture, otherwise it is recorded on its own.
+    pub fn record(&mut self, id: &str, from: f32, to: f32) {
+        if let Some(gesture) = self.gestures.get_mut(id) {
+            gesture.to = to;
+        } else {
+            self.push(Edit {
+                id: id.to_string(),
+                from,
+                to,
+            });
+        }
+    }
+
+    /// Ends the gesture for a parameter, recording it if the value has changed.
+    pub fn end_gesture(&mut self, id: &str) {
+        if let Some(gesture) = self.gestures.remove(id) {
+            self.push(gesture);
+        }
+    }
+
+    /// Pops the last edit. Its `from` value needs to be applied by the caller.
+    pub fn undo(&mut self) -> Option<Edit> {
+        let edit = self.undo_stack.pop_back()?;
+        self.redo_stack.push(edit.clone());
+        Some(edit)
+    }
+
+    /// Pops the last undone edit. Its `to` value needs to be applied by the caller.
+    pub fn redo(&mut self) -> Option<Edit> {
+        let edit = self.redo_stack.pop()?;
+        self.undo_stack.push_back(edit.clone());
+        Some(edit)
+    }
+
+    pub fn state(&self) -> HistoryState {
+        HistoryState {
+            can_undo: !self.undo_stack.is_empty(),
+            can_redo: !self.redo_stack.is_empty(),
+        }
+    }
+
+    fn push(&mut self, edit: Edit) {
+        if edit.from == edit.to || self.size == 0 {
+            return;
+        }
+
+        self.redo_stack.clear();
+        self.undo_stack.push_back(edit);
+
+        while self.undo_stack.len() > self.size {
+            self.undo_stack.pop_front();
+        }
+    }
+}
+
+impl Default for History {
+    fn default() -> Self {
+        Self::new(DEFAULT_HISTORY_SIZE)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gesture_is_a_single_edit() {
+        let mut history = History::default();
+        history.begin_gesture("gain", 0.5);
+        history.record("gain", 0.5, 0.6);
+        history.record("gain", 0.6, 0.7);
+        history.record("gain", 0.7, 0.8);
+        history.end_gesture("gain");
+
+        assert_eq!(
+            history.undo(),
+            Some(Edit {
+                id: String::from("gain"),
+                from: 0.5,
+                to: 0.8,
+            })
+        );
+        assert_eq!(history.undo(), None);
+    }
+
+    #[test]
+    fn new_edit_clears_redo() {
+        let mut history = History::default();
+        history.record("gain", 0.5, 0.6);
+        history.record("gain", 0.6, 0.7);
+        history.undo();
+        assert!(history.state().can_redo);
+
+        history.record("gain", 0.6, 0.2);
+        assert_eq!(
+            history.state(),
+            HistoryState {
+                can_undo: true,
+                can_redo: false,
+            }
+        );
+        assert_eq!(history.redo(), None);
+    }
+}
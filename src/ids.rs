@@ -0,0 +1,54 @@
+use serde::{Deserialize, Deserializer, Serializer};
+use std::borrow::Cow;
+use std::error::Error;
+use std::fmt;
+
+/// The ID of a parameter, either as a plain `String` or as the ID enum
+/// `define_params!` and `#[derive(ReactPlugParams)]` generate for a parameters
+/// struct, e.g. `ExampleParamsId` for `ExampleParams`.
+pub trait ParamId: Sized {
+    /// The ID as it's used by nih-plug and the GUI.
+    fn as_str(&self) -> Cow<'_, str>;
+
+    /// Parses an ID, or returns `None` if no parameter has it.
+    fn from_id(id: &str) -> Option<Self>;
+}
+
+impl ParamId for String {
+    fn as_str(&self) -> Cow<'_, str> {
+        Cow::Borrowed(self)
+    }
+
+    fn from_id(id: &str) -> Option<Self> {
+        Some(id.to_string())
+    }
+}
+
+/// Returned when parsing an ID that doesn't belong to any parameter of the struct.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownParamId(pub String);
+
+impl fmt::Display for UnknownParamId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Unknown parameter ID `{}`", self.0)
+    }
+}
+
+impl Error for UnknownParamId {}
+
+/// Serializes a [ParamId] as its string, for `#[serde(with = "...")]`.
+pub(crate) mod serde_param_id {
+    use super::*;
+    use serde::de;
+
+    pub fn serialize<I: ParamId, S: Serializer>(id: &I, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&id.as_str())
+    }
+
+    pub fn deserialize<'de, I: ParamId, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<I, D::Error> {
+        let id = String::deserialize(deserializer)?;
+        I::from_id(&id).ok_or_else(|| de::Error::custom(UnknownParamId(id)))
+    }
+}
@@ -1,10 +1,36 @@
+// Lets the derive macros, whose expansions refer to `react_plug::...`, be used
+// within this crate as well
+extern crate self as react_plug;
+
+pub mod bindings;
+pub mod console;
 pub mod editor;
+pub mod history;
+pub mod ids;
+pub mod lock;
+pub mod metadata;
+pub mod midi_learn;
+pub mod notes;
+pub mod presets;
 
 pub mod prelude {
-    pub use crate::editor::ReactPlugEditor;
+    pub use crate::console::LogLevel;
+    pub use crate::editor::{PluginMessageSender, ReactPlugEditor};
+    pub use crate::ids::ParamId;
+    pub use crate::midi_learn::{MidiLearn, MidiLearnId, MidiMappings};
+    pub use crate::notes::{note_queue, NoteReceiver, NoteSender};
+    pub use crate::presets::PresetManager;
+    pub use crate::ParamChange;
     pub use react_plug_derive::*;
 }
 
+use crate::console::LogLevel;
+use crate::history::HistoryState;
+pub use crate::ids::{ParamId, UnknownParamId};
+use crate::metadata::ParamInfo;
+use crate::midi_learn::{MidiLearnEvent, MidiLearnRequest};
+use crate::notes::GuiNoteEvent;
+use crate::presets::{PresetEvent, PresetRequest};
 use nih_plug::params::Params;
 pub use react_plug_derive::*;
 use serde::{Deserialize, Serialize};
@@ -12,21 +38,120 @@ use std::sync::Arc;
 
 pub type MessageChannel<M> = Arc<(crossbeam_channel::Sender<M>, crossbeam_channel::Receiver<M>)>;
 
+/// A parameter's new normalized value. The ID is a `String` by default, use
+/// [ParamChange::into_typed] to turn it into a struct's ID enum.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(bound = "I: ParamId")]
+pub struct ParamChange<I = String> {
+    #[serde(with = "ids::serde_param_id")]
+    pub id: I,
+    pub value: f32,
+    /// The value formatted by the parameter's own `value_to_string` function. Only
+    /// sent to the GUI if the editor was created using `with_value_text(true)`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+}
+
+impl ParamChange {
+    /// Parses the ID, e.g. as the `ExampleParamsId` of `ExampleParams`.
+    pub fn into_typed<I: ParamId>(self) -> Result<ParamChange<I>, UnknownParamId> {
+        Ok(ParamChange {
+            id: I::from_id(&self.id).ok_or(UnknownParamId(self.id))?,
+            value: self.value,
+            text: self.text,
+        })
+    }
+}
+
+/// Sent to the GUI if a [GuiMessage::SetParamFromString] couldn't be parsed.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ParseError {
+    pub id: String,
+    pub text: String,
+    pub message: String,
+}
+
+/// The value of a parameter as it's currently seen by the audio thread, e.g. while
+/// it's being smoothed or modulated.
 #[derive(Serialize, Deserialize, Debug)]
-pub struct ParamChange {
+pub struct ParamDisplayValue {
     pub id: String,
     pub value: f32,
 }
 
+/// The formatted display text for a normalized value of a parameter.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct FormattedValue {
+    pub id: String,
+    pub normalized: f32,
+    pub text: String,
+}
+
+/// Sent to the GUI if a [GuiMessage::FormatValue] couldn't be answered, e.g. because
+/// there is no parameter with the ID.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct FormatError {
+    pub id: String,
+    pub normalized: f32,
+    pub message: String,
+}
+
+/// The value of a persisted field of the plugin's parameters, using the same JSON
+/// representation as in the plugin's state.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PersistedField {
+    pub key: String,
+    pub value: serde_json::Value,
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub enum PluginMessage<M> {
+    /// Describes all parameters. This is sent in response to [GuiMessage::Init].
+    ParamInfo(Vec<ParamInfo>),
     ParamChange(ParamChange),
+    ParamDisplayValue(ParamDisplayValue),
+    FormattedValue(FormattedValue),
+    FormatError(FormatError),
+    ParseError(ParseError),
+    PersistedField(PersistedField),
+    History(HistoryState),
+    MidiLearn(MidiLearnEvent),
+    Preset(PresetEvent),
     Message(M),
 }
 
 #[derive(Serialize, Deserialize, Debug)]
 pub enum GuiMessage<M> {
     ParamChange(ParamChange),
+    /// Sets a parameter from text entered by the user, parsed by the parameter's own
+    /// `string_to_value` function.
+    SetParamFromString {
+        id: String,
+        text: String,
+    },
+    /// Starts a gesture for the parameter with the given ID. All changes until the
+    /// matching [GuiMessage::EndGesture] are a single step in the undo history.
+    BeginGesture(String),
+    EndGesture(String),
+    /// Requests the display text of a normalized value, which is answered with a
+    /// [PluginMessage::FormattedValue], or a [PluginMessage::FormatError] if it
+    /// couldn't be formatted.
+    FormatValue {
+        id: String,
+        normalized: f32,
+    },
     Init,
+    /// Sets a persisted field, which is then sent back to the GUI.
+    PersistedField(PersistedField),
+    Undo,
+    Redo,
+    /// A message logged by the GUI, e.g. using `console.log()`.
+    Log {
+        level: LogLevel,
+        message: String,
+    },
+    MidiLearn(MidiLearnRequest),
+    Note(GuiNoteEvent),
+    Preset(PresetRequest),
     Message(M),
 }
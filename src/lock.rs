@@ -0,0 +1,206 @@
+use crate::metadata::{ParamInfo, ParamKind};
+use nih_plug::params::Params;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// What a parameter's automation and its value in saved sessions depend on. If any of
+/// this changes, hosts will restore a different value, or none at all.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct LockedParam {
+    pub kind: ParamKind,
+    /// The plain value at a normalized value of 0.
+    pub min: f32,
+    /// The plain value at a normalized value of 1.
+    pub max: f32,
+    pub step_count: Option<usize>,
+    /// Set once a breaking change to the parameter was allowed through
+    /// `migrated_ids`, so that it's only allowed once.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub migrated: bool,
+}
+
+/// The parameters of a parameters struct, including the nested ones, by ID.
+pub type LockedParams = BTreeMap<String, LockedParam>;
+
+/// The contents of `params.lock`, the parameters of each root parameters struct by
+/// the struct's name. The bindings generator compares the parameters with the
+/// lockfile before updating it, so that changes that break existing automation don't
+/// go unnoticed.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct ParamsLock(pub BTreeMap<String, LockedParams>);
+
+impl ParamsLock {
+    /// Reads a lockfile, or returns an empty one if it doesn't exist yet.
+    pub fn read(path: &Path) -> io::Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(lock) => serde_json::from_str(&lock).map_err(|err| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("Couldn't parse {}: {}", path.display(), err),
+                )
+            }),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(err),
+        }
+    }
+
+    pub fn write(&self, path: &Path) -> io::Result<()> {
+        let lock = serde_json::to_string_pretty(self).map_err(io::Error::other)?;
+        fs::write(path, lock + "\n")
+    }
+}
+
+/// Reads the locked properties of all parameters in a parameters struct.
+pub fn locked_params<P: Params>(params: &P) -> LockedParams {
+    params
+        .param_map()
+        .into_iter()
+        .map(|(id, param, group)| {
+            // SAFETY: `params` outlives the parameter map
+            let locked = unsafe {
+                let info = ParamInfo::new(&id, param, &group);
+                LockedParam {
+                    kind: info.kind,
+                    min: param.preview_plain(0.0),
+                    max: param.preview_plain(1.0),
+                    step_count: info.step_count,
+                    migrated: false,
+                }
+            };
+
+            (id, locked)
+        })
+        .collect()
+}
+
+/// Describes the changes from `old` to `new` that break existing automation and saved
+/// sessions, i.e. removed parameters and parameters whose type or range changed.
+/// Parameters in `migrated_ids` are allowed to change, unless the lockfile already
+/// recorded a migration for them. New parameters never break anything.
+pub fn breaking_changes(
+    old: &LockedParams,
+    new: &LockedParams,
+    migrated_ids: &[&str],
+) -> Vec<String> {
+    old.iter()
+        .filter(|(id, old)| old.migrated || !migrated_ids.contains(&id.as_str()))
+        .filter_map(|(id, old)| breaking_change(id, old, new.get(id)))
+        .collect()
+}
+
+/// Marks the parameters in `new` whose breaking changes `migrated_ids` allowed, or
+/// that were already marked in `old`. This is called before writing `new` to the
+/// lockfile, so that listing an ID in `migrated_ids` only allows a single change.
+pub fn record_migrations(old: &LockedParams, new: &mut LockedParams, migrated_ids: &[&str]) {
+    for (id, param) in new.iter_mut() {
+        if let Some(old) = old.get(id) {
+            param.migrated = old.migrated
+                || (migrated_ids.contains(&id.as_str())
+                    && breaking_change(id, old, Some(param)).is_some());
+        }
+    }
+}
+
+fn breaking_change(id: &str, old: &LockedParam, new: Option<&LockedParam>) -> Option<String> {
+    match new {
+        None => Some(format!("`{}` was removed", id)),
+        Some(new) if new.kind != old.kind => Some(format!(
+            "`{}` changed from a {:?} to a {:?}",
+            id, old.kind, new.kind
+        )),
+        Some(new) if (new.min, new.max) != (old.min, old.max) => Some(format!(
+            "The range of `{}` changed from {}..{} to {}..{}",
+            id, old.min, old.max, new.min, new.max
+        )),
+        Some(new) if new.step_count != old.step_count => Some(format!(
+            "The step count of `{}` changed from {:?} to {:?}",
+            id, old.step_count, new.step_count
+        )),
+        Some(_) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn float_param(min: f32, max: f32) -> LockedParam {
+        LockedParam {
+            kind: ParamKind::FloatParam,
+            min,
+            max,
+            step_count: None,
+            migrated: false,
+        }
+    }
+
+    fn lock(params: &[(&str, LockedParam)]) -> LockedParams {
+        params
+            .iter()
+            .map(|(id, param)| (id.to_string(), param.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn reports_breaking_changes() {
+        let old = lock(&[("gain", float_param(0.0, 1.0))]);
+
+        let renamed = lock(&[("volume", float_param(0.0, 1.0))]);
+        assert_eq!(
+            breaking_changes(&old, &renamed, &[]),
+            ["`gain` was removed"]
+        );
+
+        let narrowed = lock(&[("gain", float_param(0.0, 0.5))]);
+        assert_eq!(
+            breaking_changes(&old, &narrowed, &[]),
+            ["The range of `gain` changed from 0..1 to 0..0.5"]
+        );
+
+        assert_eq!(breaking_changes(&old, &lock(&[]), &[]).len(), 1);
+    }
+
+    #[test]
+    fn new_params_dont_break_anything() {
+        let old = lock(&[("gain", float_param(0.0, 1.0))]);
+        let new = lock(&[
+            ("gain", float_param(0.0, 1.0)),
+            ("mix", float_param(0.0, 1.0)),
+        ]);
+
+        assert!(breaking_changes(&old, &new, &[]).is_empty());
+    }
+
+    #[test]
+    fn migrated_ids_are_allowed_to_change() {
+        let old = lock(&[("gain", float_param(0.0, 1.0))]);
+        let new = lock(&[("gain", float_param(0.0, 0.5))]);
+
+        assert!(breaking_changes(&old, &new, &["gain"]).is_empty());
+        assert!(breaking_changes(&old, &lock(&[]), &["gain"]).is_empty());
+    }
+
+    #[test]
+    fn migrations_are_only_allowed_once() {
+        let old = lock(&[("gain", float_param(0.0, 1.0))]);
+        let mut new = lock(&[("gain", float_param(0.0, 0.5))]);
+        record_migrations(&old, &mut new, &["gain"]);
+        assert!(new["gain"].migrated);
+
+        let newer = lock(&[("gain", float_param(0.0, 0.25))]);
+        assert_eq!(breaking_changes(&new, &newer, &["gain"]).len(), 1);
+    }
+
+    #[test]
+    fn unchanged_params_arent_marked_as_migrated() {
+        let old = lock(&[("gain", float_param(0.0, 1.0))]);
+        let mut new = old.clone();
+        record_migrations(&old, &mut new, &["gain"]);
+
+        assert!(!new["gain"].migrated);
+    }
+}
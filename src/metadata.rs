@@ -0,0 +1,123 @@
+use nih_plug::params::ParamFlags;
+use nih_plug::prelude::ParamPtr;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// The type of a parameter.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParamKind {
+    FloatParam,
+    IntParam,
+    BoolParam,
+    EnumParam,
+}
+
+/// Mirrors nih-plug's [ParamFlags].
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ParamInfoFlags {
+    pub bypass: bool,
+    pub non_automatable: bool,
+    pub hidden: bool,
+    pub hide_in_generic_ui: bool,
+}
+
+impl From<ParamFlags> for ParamInfoFlags {
+    fn from(flags: ParamFlags) -> Self {
+        Self {
+            bypass: flags.contains(ParamFlags::BYPASS),
+            non_automatable: flags.contains(ParamFlags::NON_AUTOMATABLE),
+            hidden: flags.contains(ParamFlags::HIDDEN),
+            hide_in_generic_ui: flags.contains(ParamFlags::HIDE_IN_GENERIC_UI),
+        }
+    }
+}
+
+/// Describes a parameter at runtime. This is sent to the GUI on initialization, so
+/// that GUIs can also display parameters that don't have generated bindings.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ParamInfo {
+    pub id: String,
+    pub kind: ParamKind,
+    pub name: String,
+    /// The parameter's description, from its doc comment or `description` field.
+    pub description: Option<String>,
+    pub unit: String,
+    /// The path of the group this parameter is nested in, e.g. `"Band 1/Filter"`.
+    /// Empty for top-level parameters.
+    pub group: String,
+    pub step_count: Option<usize>,
+    pub default_normalized_value: f32,
+    pub flags: ParamInfoFlags,
+    /// The names of all variants, for enum parameters.
+    pub variants: Option<Vec<String>>,
+    pub poly_modulation_id: Option<u32>,
+}
+
+impl ParamInfo {
+    /// Describes the parameter behind a [ParamPtr].
+    ///
+    /// # Safety
+    ///
+    /// The parameter needs to outlive this call, see [ParamPtr].
+    pub unsafe fn new(id: &str, param: ParamPtr, group: &str) -> Self {
+        let kind = match param {
+            ParamPtr::FloatParam(_) => ParamKind::FloatParam,
+            ParamPtr::IntParam(_) => ParamKind::IntParam,
+            ParamPtr::BoolParam(_) => ParamKind::BoolParam,
+            ParamPtr::EnumParam(_) => ParamKind::EnumParam,
+        };
+
+        // The names of an enum's variants are what its values get formatted as
+        let variants = match (kind, param.step_count()) {
+            (ParamKind::EnumParam, Some(step_count)) => Some(
+                (0..=step_count)
+                    .map(|step| {
+                        let normalized = if step_count == 0 {
+                            0.0
+                        } else {
+                            step as f32 / step_count as f32
+                        };
+                        param.normalized_value_to_string(normalized, false)
+                    })
+                    .collect(),
+            ),
+            _ => None,
+        };
+
+        Self {
+            id: id.to_string(),
+            kind,
+            name: param.name().to_string(),
+            description: None,
+            unit: param.unit().to_string(),
+            group: group.to_string(),
+            step_count: param.step_count(),
+            default_normalized_value: param.default_normalized_value(),
+            flags: param.flags().into(),
+            variants,
+            poly_modulation_id: param.poly_modulation_id(),
+        }
+    }
+}
+
+/// Describes all parameters in a parameter map, as returned by
+/// [Params::param_map](nih_plug::params::Params::param_map), along with their
+/// descriptions by ID.
+///
+/// # Safety
+///
+/// The parameters need to outlive this call, see [ParamPtr].
+pub unsafe fn param_metadata(
+    param_map: &[(String, ParamPtr, String)],
+    descriptions: &HashMap<String, String>,
+) -> Vec<ParamInfo> {
+    param_map
+        .iter()
+        .map(|(id, param, group)| ParamInfo {
+            description: descriptions.get(id).cloned(),
+            ..ParamInfo::new(id, *param, group)
+        })
+        .collect()
+}
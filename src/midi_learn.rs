@@ -0,0 +1,290 @@
+use crate::UnknownParamId;
+use nih_plug::midi::NoteEvent;
+use nih_plug::params::Params;
+use react_plug_derive::ReactPlugParams;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+
+/// How many incoming CCs can be queued while a parameter is waiting for one. Only
+/// the first one is learned, so this doesn't need to be large.
+const LEARN_QUEUE_SIZE: usize = 16;
+
+/// How many mapped CC values can be queued for the editor. CCs received while the
+/// queue is full are dropped.
+const CC_QUEUE_SIZE: usize = 1024;
+
+/// Maps MIDI CC numbers to parameter IDs. CCs are mapped regardless of their MIDI
+/// channel.
+pub type MidiMappings = BTreeMap<u8, String>;
+
+/// MIDI learn operations that the GUI can request.
+#[derive(Serialize, Deserialize, Debug)]
+pub enum MidiLearnRequest {
+    /// Lists all mappings.
+    List,
+    /// Maps the next incoming CC to the parameter with this ID.
+    Learn(String),
+    /// Stops waiting for a CC.
+    Cancel,
+    /// Removes all mappings of the parameter with this ID.
+    Forget(String),
+}
+
+/// MIDI learn updates that are sent back to the GUI.
+#[derive(Serialize, Deserialize, Debug)]
+pub enum MidiLearnEvent {
+    /// The parameter ID that is waiting for a CC, if any.
+    Learning(Option<String>),
+    Learned {
+        cc: u8,
+        id: String,
+    },
+    Mappings(MidiMappings),
+}
+
+/// MIDI learn for parameters. It's nested in the plugin's parameters, so that the
+/// mappings are saved with the plugin state, and passed to the editor using
+/// `with_midi_learn()`.
+///
+/// Incoming CCs are passed to [MidiLearn::handle_event] in `process()`. Parameters
+/// can only be set in a way that notifies the host from the GUI thread, so mapped
+/// CCs are queued there and applied by the editor. This means that **learned CCs
+/// only have an effect while the editor is open**. CCs received while it's closed
+/// are ignored.
+///
+/// ## Example
+///
+/// ```rust,ignore
+/// // In define_params!
+/// #[nested]
+/// midi_learn: Arc<MidiLearn>,
+///
+/// // In process()
+/// while let Some(event) = context.next_event() {
+///     self.params.midi_learn.handle_event(&event);
+/// }
+///
+/// // In editor()
+/// ReactPlugEditor::new(self.params.clone(), &EDITOR_DIR, (800, 600))
+///     .with_midi_learn(self.params.midi_learn.clone())
+/// ```
+#[derive(Params, ReactPlugParams)]
+#[react_plug(nested)]
+pub struct MidiLearn {
+    #[persist = "midi-mappings"]
+    #[react_plug(skip)]
+    mappings: Arc<RwLock<MidiMappings>>,
+    learning: Mutex<Option<String>>,
+    /// Whether a parameter is waiting for a CC, so that the audio thread doesn't
+    /// need to lock `learning`.
+    is_learning: AtomicBool,
+    /// Whether the editor is open, and mapped CCs should be queued for it.
+    editor_open: AtomicBool,
+    /// The IDs of the parameters CCs can be mapped to, as passed to the editor.
+    param_ids: RwLock<HashSet<String>>,
+    learn_sender: crossbeam_channel::Sender<u8>,
+    learn_receiver: crossbeam_channel::Receiver<u8>,
+    cc_sender: crossbeam_channel::Sender<(u8, f32)>,
+    cc_receiver: crossbeam_channel::Receiver<(u8, f32)>,
+}
+
+impl Default for MidiLearn {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MidiLearn {
+    pub fn new() -> Self {
+        let (learn_sender, learn_receiver) = crossbeam_channel::bounded(LEARN_QUEUE_SIZE);
+        let (cc_sender, cc_receiver) = crossbeam_channel::bounded(CC_QUEUE_SIZE);
+
+        Self {
+            mappings: Arc::new(RwLock::new(MidiMappings::new())),
+            learning: Mutex::new(None),
+            is_learning: AtomicBool::new(false),
+            editor_open: AtomicBool::new(false),
+            param_ids: RwLock::new(HashSet::new()),
+            learn_sender,
+            learn_receiver,
+            cc_sender,
+            cc_receiver,
+        }
+    }
+
+    /// The current mappings.
+    pub fn mappings(&self) -> MidiMappings {
+        self.mappings.read().unwrap().clone()
+    }
+
+    /// Handles an incoming event. Call this from `process()`. This doesn't allocate
+    /// or block.
+    ///
+    /// While a parameter is waiting for a CC, CCs are passed on to the editor to be
+    /// learned. Otherwise, CCs that are mapped to a parameter are queued for the
+    /// editor, which sets the parameter, if it's open. Returns whether the event
+    /// was one of those CCs.
+    pub fn handle_event<S>(&self, event: &NoteEvent<S>) -> bool {
+        let (cc, value) = match event {
+            NoteEvent::MidiCC { cc, value, .. } => (*cc, *value),
+            _ => return false,
+        };
+
+        if self.is_learning.load(Ordering::Relaxed) {
+            let _ = self.learn_sender.try_send(cc);
+            return true;
+        }
+
+        if !self.editor_open.load(Ordering::Relaxed) {
+            return false;
+        }
+
+        let is_mapped = match self.mappings.try_read() {
+            Ok(mappings) => mappings.contains_key(&cc),
+            Err(_) => false,
+        };
+        if is_mapped {
+            let _ = self.cc_sender.try_send((cc, value));
+        }
+
+        is_mapped
+    }
+
+    /// Waits for the next CC to map it to the parameter with this ID. Only the
+    /// parameters of the editor this was passed to can be mapped.
+    pub fn learn(&self, id: impl Into<String>) -> Result<(), UnknownParamId> {
+        let id = id.into();
+        if !self.param_ids.read().unwrap().contains(&id) {
+            return Err(UnknownParamId(id));
+        }
+
+        *self.learning.lock().unwrap() = Some(id);
+
+        // CCs that were queued for an earlier request shouldn't be learned
+        while self.learn_receiver.try_recv().is_ok() {}
+        self.is_learning.store(true, Ordering::Relaxed);
+
+        Ok(())
+    }
+
+    pub fn cancel(&self) {
+        self.is_learning.store(false, Ordering::Relaxed);
+        *self.learning.lock().unwrap() = None;
+    }
+
+    /// The parameter ID that is waiting for a CC, if any.
+    pub fn learning(&self) -> Option<String> {
+        self.learning.lock().unwrap().clone()
+    }
+
+    /// Removes all mappings of the parameter with this ID.
+    pub fn forget(&self, id: &str) {
+        self.mappings
+            .write()
+            .unwrap()
+            .retain(|_, mapped_id| mapped_id != id);
+    }
+
+    pub(crate) fn set_param_ids(&self, ids: impl IntoIterator<Item = String>) {
+        *self.param_ids.write().unwrap() = ids.into_iter().collect();
+    }
+
+    /// Starts queueing mapped CCs for the editor, until the returned guard is
+    /// dropped together with the editor's window.
+    pub(crate) fn open_editor(self: &Arc<Self>) -> EditorGuard {
+        while self.cc_receiver.try_recv().is_ok() {}
+        self.editor_open.store(true, Ordering::Relaxed);
+
+        EditorGuard(self.clone())
+    }
+
+    /// The next queued CC value, along with the ID of the parameter it's mapped to.
+    pub(crate) fn next_mapped_cc(&self) -> Option<(String, f32)> {
+        while let Ok((cc, value)) = self.cc_receiver.try_recv() {
+            // The mapping may have been removed since the CC was queued
+            if let Some(id) = self.mappings.read().unwrap().get(&cc) {
+                return Some((id.clone(), value));
+            }
+        }
+
+        None
+    }
+
+    /// Maps the next queued CC to the parameter that is waiting for one. Returns the
+    /// CC and the parameter ID if a mapping was learned.
+    pub(crate) fn try_learn(&self) -> Option<(u8, String)> {
+        let cc = self.learn_receiver.try_recv().ok()?;
+        self.is_learning.store(false, Ordering::Relaxed);
+        let id = self.learning.lock().unwrap().take()?;
+
+        let mut mappings = self.mappings.write().unwrap();
+        mappings.retain(|_, mapped_id| mapped_id != &id);
+        mappings.insert(cc, id.clone());
+
+        Some((cc, id))
+    }
+}
+
+/// Stops queueing mapped CCs when the editor is closed, see [MidiLearn::open_editor].
+pub(crate) struct EditorGuard(Arc<MidiLearn>);
+
+impl Drop for EditorGuard {
+    fn drop(&mut self) {
+        self.0.editor_open.store(false, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cc(cc: u8, value: f32) -> NoteEvent<()> {
+        NoteEvent::MidiCC {
+            timing: 0,
+            channel: 0,
+            cc,
+            value,
+        }
+    }
+
+    fn midi_learn() -> Arc<MidiLearn> {
+        let midi_learn = Arc::new(MidiLearn::new());
+        midi_learn.set_param_ids([String::from("gain")]);
+        midi_learn
+    }
+
+    #[test]
+    fn learn_rejects_unknown_ids() {
+        let midi_learn = midi_learn();
+
+        assert_eq!(
+            midi_learn.learn("volume"),
+            Err(UnknownParamId(String::from("volume")))
+        );
+        assert_eq!(midi_learn.learning(), None);
+    }
+
+    #[test]
+    fn mapped_ccs_are_queued_while_the_editor_is_open() {
+        let midi_learn = midi_learn();
+        midi_learn.learn("gain").unwrap();
+        assert!(midi_learn.handle_event(&cc(7, 0.0)));
+        assert_eq!(midi_learn.try_learn(), Some((7, String::from("gain"))));
+
+        assert!(!midi_learn.handle_event(&cc(7, 0.25)));
+
+        let editor = midi_learn.open_editor();
+        assert!(midi_learn.handle_event(&cc(7, 0.5)));
+        assert!(!midi_learn.handle_event(&cc(8, 0.5)));
+        assert_eq!(
+            midi_learn.next_mapped_cc(),
+            Some((String::from("gain"), 0.5))
+        );
+        assert_eq!(midi_learn.next_mapped_cc(), None);
+
+        drop(editor);
+        assert!(!midi_learn.handle_event(&cc(7, 0.75)));
+    }
+}
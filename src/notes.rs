@@ -0,0 +1,121 @@
+use nih_plug::midi::NoteEvent;
+use serde::{Deserialize, Serialize};
+use std::time::Instant;
+
+/// How many note events from the GUI can be queued before `process()` picks them up.
+const QUEUE_SIZE: usize = 1024;
+
+/// A note event sent by the GUI, e.g. by an on-screen keyboard. Velocities and pitch
+/// bend values are normalized, just like in nih-plug's [NoteEvent]s.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum GuiNoteEvent {
+    NoteOn {
+        channel: u8,
+        note: u8,
+        velocity: f32,
+    },
+    NoteOff {
+        channel: u8,
+        note: u8,
+        velocity: f32,
+    },
+    PitchBend {
+        channel: u8,
+        value: f32,
+    },
+}
+
+impl GuiNoteEvent {
+    fn into_note_event<S>(self, timing: u32) -> NoteEvent<S> {
+        match self {
+            GuiNoteEvent::NoteOn {
+                channel,
+                note,
+                velocity,
+            } => NoteEvent::NoteOn {
+                timing,
+                voice_id: None,
+                channel,
+                note,
+                velocity,
+            },
+            GuiNoteEvent::NoteOff {
+                channel,
+                note,
+                velocity,
+            } => NoteEvent::NoteOff {
+                timing,
+                voice_id: None,
+                channel,
+                note,
+                velocity,
+            },
+            GuiNoteEvent::PitchBend { channel, value } => NoteEvent::MidiPitchBend {
+                timing,
+                channel,
+                value,
+            },
+        }
+    }
+}
+
+/// Creates a queue for note events from the GUI. The [NoteSender] is passed to the
+/// editor using `with_note_sender()`, the [NoteReceiver] is drained in `process()`.
+pub fn note_queue() -> (NoteSender, NoteReceiver) {
+    let (sender, receiver) = crossbeam_channel::bounded(QUEUE_SIZE);
+
+    (
+        NoteSender { sender },
+        NoteReceiver {
+            receiver,
+            last_block: None,
+        },
+    )
+}
+
+/// Sends note events from the GUI to the audio thread.
+#[derive(Clone)]
+pub struct NoteSender {
+    sender: crossbeam_channel::Sender<(Instant, GuiNoteEvent)>,
+}
+
+impl NoteSender {
+    /// Queues a note event. Returns `false` if the queue is full.
+    pub fn send(&self, event: GuiNoteEvent) -> bool {
+        self.sender.try_send((Instant::now(), event)).is_ok()
+    }
+}
+
+/// Receives note events from the GUI on the audio thread.
+pub struct NoteReceiver {
+    receiver: crossbeam_channel::Receiver<(Instant, GuiNoteEvent)>,
+    last_block: Option<Instant>,
+}
+
+impl NoteReceiver {
+    /// Returns the note events that were sent since the last block. Call this once
+    /// per `process()` call. This doesn't allocate or block.
+    ///
+    /// The events are delayed by one block. Their timing offsets preserve the time
+    /// between them, relative to when the last block was processed.
+    pub fn events<S>(&mut self, num_samples: usize) -> impl Iterator<Item = NoteEvent<S>> + '_ {
+        let now = Instant::now();
+        let last_block = self.last_block.replace(now);
+        let max_timing = num_samples.saturating_sub(1) as u32;
+
+        std::iter::from_fn(move || {
+            let (received, event) = self.receiver.try_recv().ok()?;
+
+            let timing = match last_block {
+                Some(last_block) if now > last_block => {
+                    let position = received.saturating_duration_since(last_block).as_secs_f64()
+                        / (now - last_block).as_secs_f64();
+                    ((position * num_samples as f64) as u32).min(max_timing)
+                }
+                _ => 0,
+            };
+
+            Some(event.into_note_event(timing))
+        })
+    }
+}
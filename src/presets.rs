@@ -0,0 +1,263 @@
+use include_dir::Dir;
+use nih_plug::prelude::Plugin;
+use nih_plug::wrapper::state::PluginState;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// The file extension used for preset files, both for user and factory presets.
+pub const PRESET_EXTENSION: &str = "json";
+
+/// A preset file. This is what gets written to and read from disk.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Preset {
+    pub name: String,
+    pub state: PluginState,
+}
+
+/// Describes a preset without loading its state.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct PresetInfo {
+    pub name: String,
+    /// Whether this preset is a factory preset. Factory presets are read-only.
+    pub factory: bool,
+}
+
+/// Preset operations that the GUI can request.
+#[derive(Serialize, Deserialize, Debug)]
+pub enum PresetRequest {
+    List,
+    Load(PresetInfo),
+    Save(String),
+    Rename { from: String, to: String },
+    Delete(String),
+}
+
+/// Preset updates that are sent back to the GUI.
+#[derive(Serialize, Deserialize, Debug)]
+pub enum PresetEvent {
+    List(Vec<PresetInfo>),
+    Loaded(PresetInfo),
+    Error(String),
+}
+
+/// Manages the presets of a plugin. User presets are stored as JSON files inside a
+/// per-plugin directory, factory presets can be embedded into the plugin using
+/// [include_dir](include_dir::include_dir), just like the GUI.
+///
+/// ## Example
+///
+/// ```rust,ignore
+/// static FACTORY_PRESETS: Dir = include_dir!("$CARGO_MANIFEST_DIR/presets");
+///
+/// let presets = PresetManager::for_plugin::<ExamplePlugin>()
+///     .with_factory_presets(&FACTORY_PRESETS);
+/// ```
+pub struct PresetManager {
+    user_dir: PathBuf,
+    factory_presets: Option<&'static Dir<'static>>,
+}
+
+impl PresetManager {
+    /// Creates a preset manager that stores user presets in `user_dir`.
+    pub fn new(user_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            user_dir: user_dir.into(),
+            factory_presets: None,
+        }
+    }
+
+    /// Creates a preset manager that stores user presets in the user's data
+    /// directory, under `<vendor>/<plugin name>/Presets`.
+    pub fn for_plugin<P: Plugin>() -> Self {
+        let data_dir = dirs::data_dir().unwrap_or_else(std::env::temp_dir);
+        Self::new(data_dir.join(P::VENDOR).join(P::NAME).join("Presets"))
+    }
+
+    /// Adds embedded factory presets. Every `.json` file at the root of `dir` is
+    /// treated as a [Preset].
+    pub fn with_factory_presets(mut self, dir: &'static Dir<'static>) -> Self {
+        self.factory_presets = Some(dir);
+        self
+    }
+
+    /// The directory user presets are stored in.
+    pub fn user_dir(&self) -> &Path {
+        &self.user_dir
+    }
+
+    /// Lists all factory presets, followed by all user presets. Both are sorted by
+    /// name.
+    pub fn list(&self) -> Vec<PresetInfo> {
+        let mut factory = self
+            .factory_presets
+            .iter()
+            .flat_map(|dir| dir.files())
+            .filter_map(|file| preset_name(file.path()))
+            .map(|name| PresetInfo {
+                name,
+                factory: true,
+            })
+            .collect::<Vec<_>>();
+        factory.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let mut user = fs::read_dir(&self.user_dir)
+            .into_iter()
+            .flatten()
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| preset_name(&entry.path()))
+            .map(|name| PresetInfo {
+                name,
+                factory: false,
+            })
+            .collect::<Vec<_>>();
+        user.sort_by(|a, b| a.name.cmp(&b.name));
+
+        factory.extend(user);
+        factory
+    }
+
+    /// Reads the state of a preset.
+    pub fn load(&self, preset: &PresetInfo) -> io::Result<PluginState> {
+        let preset: Preset = if preset.factory {
+            let file = self
+                .factory_presets
+                .and_then(|dir| dir.get_file(file_name(&preset.name)?))
+                .ok_or_else(|| not_found(&preset.name))?;
+            serde_json::from_slice(file.contents())?
+        } else {
+            let contents = fs::read(self.user_path(&preset.name)?)?;
+            serde_json::from_slice(&contents)?
+        };
+
+        Ok(preset.state)
+    }
+
+    /// Saves a state as a user preset, overwriting any user preset with the same
+    /// name.
+    pub fn save(&self, name: &str, state: PluginState) -> io::Result<()> {
+        let path = self.user_path(name)?;
+        fs::create_dir_all(&self.user_dir)?;
+
+        let preset = Preset {
+            name: name.to_string(),
+            state,
+        };
+        fs::write(path, serde_json::to_vec_pretty(&preset)?)
+    }
+
+    /// Renames a user preset.
+    pub fn rename(&self, from: &str, to: &str) -> io::Result<()> {
+        let from_path = self.user_path(from)?;
+        let to_path = self.user_path(to)?;
+
+        if to_path.exists() {
+            return Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                format!(r#"A preset named "{}" already exists"#, to),
+            ));
+        }
+
+        let mut preset: Preset = serde_json::from_slice(&fs::read(&from_path)?)?;
+        preset.name = to.to_string();
+        fs::write(&to_path, serde_json::to_vec_pretty(&preset)?)?;
+        fs::remove_file(from_path)
+    }
+
+    /// Deletes a user preset.
+    pub fn delete(&self, name: &str) -> io::Result<()> {
+        fs::remove_file(self.user_path(name)?)
+    }
+
+    fn user_path(&self, name: &str) -> io::Result<PathBuf> {
+        file_name(name)
+            .map(|file_name| self.user_dir.join(file_name))
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!(r#"Invalid preset name: "{}""#, name),
+                )
+            })
+    }
+}
+
+/// Names of devices that Windows reserves in every directory, regardless of the
+/// extension.
+const RESERVED_NAMES: &[&str] = &[
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+    "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// Returns the file name for a preset, or `None` if the name can't be used as one on
+/// every platform.
+fn file_name(name: &str) -> Option<String> {
+    let stem = name.split('.').next().unwrap_or_default().trim_end();
+    let invalid = name.trim().is_empty()
+        || name.starts_with('.')
+        || name.chars().any(|c| {
+            c.is_control() || matches!(c, '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|')
+        })
+        || RESERVED_NAMES
+            .iter()
+            .any(|reserved| stem.eq_ignore_ascii_case(reserved));
+
+    (!invalid).then(|| format!("{}.{}", name, PRESET_EXTENSION))
+}
+
+fn preset_name(path: &Path) -> Option<String> {
+    if path.extension()? != PRESET_EXTENSION {
+        return None;
+    }
+
+    path.file_stem()
+        .and_then(|stem| stem.to_str())
+        .map(str::to_string)
+}
+
+fn not_found(name: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::NotFound,
+        format!(r#"Couldn't find preset "{}""#, name),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn user_path_rejects_invalid_names() {
+        let presets = PresetManager::new("presets");
+
+        for name in [
+            "",
+            " ",
+            "..",
+            ".hidden",
+            "../preset",
+            "..\\preset",
+            "sub/preset",
+            "sub\\preset",
+            "/preset",
+            "Bass\0",
+            "Bass\n1",
+            "CON",
+            "con",
+            "Com1",
+            "LPT9",
+            "nul.backup",
+            "AUX .old",
+        ] {
+            let err = presets.user_path(name).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidInput, "{:?}", name);
+        }
+
+        for name in ["Bass 1", "Console", "COM10", "Lead v1.2"] {
+            assert_eq!(
+                presets.user_path(name).unwrap(),
+                Path::new("presets").join(format!("{}.json", name))
+            );
+        }
+    }
+}